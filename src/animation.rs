@@ -0,0 +1,183 @@
+use crate::events::{Event, EventBus, Subscriber};
+use crate::{Board, Direction, Game};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub const SLIDE_DURATION: Duration = Duration::from_millis(100);
+// scrambles are played fast, and long ones even faster so they don't keep the player waiting
+pub const SCRAMBLE_MOVE_DURATION: Duration = Duration::from_millis(15);
+pub const MAX_SCRAMBLE_DURATION: Duration = Duration::from_secs(2);
+
+// where the tiles of a board are drawn, which lags behind the board itself while tiles slide into
+// their new cell. moves are picked up by comparing the board to how it looked on the last update
+pub struct TileAnimation {
+    cells: Vec<i32>,
+    // the cell each sliding tile comes from and when it left it
+    slides: HashMap<i32, (usize, Instant)>,
+    slide_duration: Duration,
+}
+
+impl TileAnimation {
+    // speed is the animation speed from the config, 2 slides twice as fast
+    pub fn new(speed: f32) -> TileAnimation {
+        TileAnimation {
+            cells: Vec::new(),
+            slides: HashMap::new(),
+            slide_duration: SLIDE_DURATION.div_f32(speed),
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.slide_duration = SLIDE_DURATION.div_f32(speed);
+    }
+
+    // starts sliding the tiles that moved since the last update. if any tile moved further than a
+    // single cell, e.g. for a new board, the tiles jump to their cells instead
+    pub fn update(&mut self, board: &Board) {
+        let now = Instant::now();
+        let slide_duration = self.slide_duration;
+        self.slides.retain(|_, (_, started)| now.duration_since(*started) < slide_duration);
+
+        if self.cells.len() != board.cells.len() {
+            self.slides.clear();
+        } else if self.cells != board.cells {
+            let width = board.width as usize;
+            let moved: Vec<(i32, usize, usize)> = board
+                .cells
+                .iter()
+                .enumerate()
+                .filter(|(index, tile)| !board.is_empty_value(**tile) && self.cells[*index] != **tile)
+                .filter_map(|(index, tile)| {
+                    let from = self.cells.iter().position(|cell| cell == tile)?;
+                    Some((*tile, from, index))
+                })
+                .collect();
+
+            let is_slide = |from: usize, to: usize| {
+                (from / width).abs_diff(to / width) + (from % width).abs_diff(to % width) == 1
+            };
+
+            if moved.iter().all(|(_, from, to)| is_slide(*from, *to)) {
+                for (tile, from, _) in moved {
+                    self.slides.insert(tile, (from, now));
+                }
+            } else {
+                self.slides.clear();
+            }
+        }
+
+        self.cells.clone_from(&board.cells);
+    }
+
+    // the column and row to draw the tile at, in between two cells while it slides
+    pub fn position(&self, tile: i32, index: usize, width: u8) -> (f32, f32) {
+        let width = width as usize;
+        let (column, row) = ((index % width) as f32, (index / width) as f32);
+
+        match self.slides.get(&tile) {
+            Some((from, started)) => {
+                let progress = ease_out(started.elapsed().as_secs_f32() / self.slide_duration.as_secs_f32());
+                let (from_column, from_row) = ((from % width) as f32, (from / width) as f32);
+
+                (
+                    from_column + (column - from_column) * progress,
+                    from_row + (row - from_row) * progress,
+                )
+            }
+            None => (column, row),
+        }
+    }
+}
+
+// fast at first, slowing down as the tile arrives
+fn ease_out(progress: f32) -> f32 {
+    1.0 - (1.0 - progress.clamp(0.0, 1.0)).powi(3)
+}
+
+// a new level being scrambled in front of the player, starting from the solved board. it is only
+// for show, the game's board is scrambled already
+pub struct ScrambleAnimation {
+    pub board: Board,
+    moves: Vec<Direction>,
+    played: usize,
+    started: Instant,
+    move_duration: Duration,
+    // the cells of the scrambled board, once they don't match the game's board it has moved on
+    scrambled: Vec<i32>,
+}
+
+impl ScrambleAnimation {
+    pub fn new(scrambled: &Board, moves: Vec<Direction>, speed: f32) -> ScrambleAnimation {
+        let move_duration = SCRAMBLE_MOVE_DURATION.min(MAX_SCRAMBLE_DURATION / moves.len().max(1) as u32);
+        let move_duration = move_duration.div_f32(speed);
+
+        // the moves lead away from the goal of the scrambled board, with its walls and variant
+        let mut board = Board {
+            cells: scrambled.goal_cells(),
+            second_blank: false,
+            ..scrambled.clone()
+        };
+        board.check_solved();
+
+        ScrambleAnimation {
+            board,
+            moves,
+            played: 0,
+            started: Instant::now(),
+            move_duration,
+            scrambled: scrambled.cells.clone(),
+        }
+    }
+
+    // plays the moves that are due by now
+    pub fn update(&mut self) {
+        let due = (self.started.elapsed().as_secs_f64() / self.move_duration.as_secs_f64()) as usize;
+
+        while self.played < due.min(self.moves.len()) {
+            self.board.move_empty(self.moves[self.played]);
+            self.played += 1;
+        }
+    }
+
+    pub fn is_finished_for(&self, board: &Board) -> bool {
+        self.played == self.moves.len() || self.scrambled != board.cells
+    }
+}
+
+// sets up the animation of every new scramble, for the game to play while it is being played.
+// the endgame trainer deals its positions right away
+pub struct Scrambles {
+    animation: Option<ScrambleAnimation>,
+    enabled: bool,
+    speed: f32,
+}
+
+impl Scrambles {
+    pub fn new(enabled: bool, speed: f32) -> Scrambles {
+        Scrambles {
+            animation: None,
+            enabled,
+            speed,
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    // the animation of the last scramble, if it wasn't picked up yet
+    pub fn take(&mut self) -> Option<ScrambleAnimation> {
+        self.animation.take()
+    }
+}
+
+impl Subscriber for Scrambles {
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        if event != Event::ScrambleStarted {
+            return;
+        }
+
+        let animated = self.enabled && !game.endgame && !game.scramble_moves.is_empty();
+        self.animation = animated.then(|| ScrambleAnimation::new(&game.board, game.scramble_moves.clone(), self.speed));
+    }
+}
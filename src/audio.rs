@@ -0,0 +1,328 @@
+use crate::config::Config;
+use crate::events::{Event, EventBus, Subscriber};
+use crate::Game;
+use rand::Rng;
+use raylib::prelude::*;
+use std::f32::consts::TAU;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SAMPLE_RATE: u32 = 44100;
+const MUSIC_EXTENSIONS: [&str; 2] = ["ogg", "mp3"];
+// the music is turned down this far while the win fanfare plays
+const DUCKED_VOLUME: f32 = 0.25;
+const DUCK_DURATION: Duration = Duration::from_millis(1500);
+
+// the volumes of the audio settings, from 0 to 1. the master volume applies to everything
+#[derive(Clone, Copy)]
+pub struct Volumes {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+}
+
+impl Volumes {
+    pub const NAMES: [&'static str; 3] = ["Master", "Music", "Sound effects"];
+
+    pub fn new(config: &Config) -> Volumes {
+        Volumes {
+            master: config.volume,
+            music: config.music_volume,
+            effects: config.effects_volume,
+        }
+    }
+
+    // the volumes by their index in NAMES
+    pub fn get_mut(&mut self, index: usize) -> &mut f32 {
+        match index {
+            0 => &mut self.master,
+            1 => &mut self.music,
+            _ => &mut self.effects,
+        }
+    }
+
+    pub fn save_to(&self, config: &mut Config) {
+        config.volume = self.master;
+        config.music_volume = self.music;
+        config.effects_volume = self.effects;
+    }
+}
+
+// sound effects, synthesized on startup so there are no audio files to ship, and the background
+// music, played from the music folder
+pub struct Audio {
+    device: RaylibAudio,
+    slide: Sound,
+    thud: Sound,
+    win: Sound,
+    personal_best: Sound,
+    tracks: Vec<PathBuf>,
+    track: usize,
+    music: Option<Music>,
+    volumes: Volumes,
+    // muting everything, or only the music
+    muted: bool,
+    music_muted: bool,
+    // how far into the track the music was on the last update, to notice it starting over
+    music_time: f32,
+    ducked_until: Option<Instant>,
+}
+
+impl Audio {
+    // None if there is no audio device, the game plays on silently then
+    pub fn new(config: &Config, thread: &RaylibThread) -> Option<Audio> {
+        let device = RaylibAudio::init_audio_device();
+
+        if !device.is_audio_device_ready() {
+            return None;
+        }
+
+        let slide = load(&swoosh(0.07))?;
+        // low and short, a bump against the edge of the board
+        let thud = load(&notes(&[(90.0, 0.12)]))?;
+        // a rising fanfare, G C E G
+        let win = load(&notes(&[(392.0, 0.12), (523.25, 0.12), (659.26, 0.12), (783.99, 0.45)]))?;
+        // an octave higher, C E G C
+        let personal_best = load(&notes(&[(523.25, 0.1), (659.26, 0.1), (783.99, 0.1), (1046.5, 0.35)]))?;
+
+        let mut audio = Audio {
+            device,
+            slide,
+            thud,
+            win,
+            personal_best,
+            tracks: find_tracks(&config.music_dir),
+            track: 0,
+            music: None,
+            volumes: Volumes::new(config),
+            muted: config.muted,
+            music_muted: config.music_muted,
+            music_time: 0.0,
+            ducked_until: None,
+        };
+
+        audio.set_volumes(audio.volumes);
+        audio.play_track(thread, 0);
+        Some(audio)
+    }
+
+    pub fn set_volumes(&mut self, volumes: Volumes) {
+        self.volumes = volumes;
+        self.device.set_master_volume(if self.muted { 0.0 } else { volumes.master });
+
+        for sound in [&mut self.slide, &mut self.thud, &mut self.win, &mut self.personal_best] {
+            self.device.set_sound_volume(sound, volumes.effects);
+        }
+
+        self.apply_music_volume();
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.set_volumes(self.volumes);
+    }
+
+    // keeps the music streaming and moves on to the next track when one ends. raylib loops the
+    // music on its own, so a track has ended once it starts over
+    pub fn update(&mut self, thread: &RaylibThread) {
+        let music = match self.music.as_mut() {
+            Some(music) => music,
+            None => return,
+        };
+
+        self.device.update_music_stream(music);
+        let time = self.device.get_music_time_played(music);
+
+        if time < self.music_time {
+            self.next_track(thread);
+            return;
+        }
+
+        self.music_time = time;
+
+        if self.ducked_until.is_some_and(|until| Instant::now() >= until) {
+            self.ducked_until = None;
+            self.apply_music_volume();
+        }
+    }
+
+    pub fn next_track(&mut self, thread: &RaylibThread) {
+        self.play_track(thread, self.track + 1);
+    }
+
+    // switches the music over to the tracks of another folder, e.g. the ambient music of zen mode.
+    // the music keeps playing if there are none
+    pub fn play_music_from(&mut self, thread: &RaylibThread, dir: &Path) {
+        let tracks = find_tracks(dir);
+
+        if !tracks.is_empty() {
+            self.tracks = tracks;
+            self.play_track(thread, 0);
+        }
+    }
+
+    pub fn is_music_muted(&self) -> bool {
+        self.music_muted
+    }
+
+    pub fn set_music_muted(&mut self, muted: bool) {
+        self.music_muted = muted;
+        self.apply_music_volume();
+    }
+
+    // starts the track at the index, skipping over tracks that can't be loaded
+    fn play_track(&mut self, thread: &RaylibThread, index: usize) {
+        if let Some(mut music) = self.music.take() {
+            self.device.stop_music_stream(&mut music);
+        }
+
+        for offset in 0..self.tracks.len() {
+            let track = (index + offset) % self.tracks.len();
+
+            match Music::load_music_stream(thread, &self.tracks[track].to_string_lossy()) {
+                Ok(mut music) => {
+                    self.device.play_music_stream(&mut music);
+                    self.music = Some(music);
+                    self.track = track;
+                    self.music_time = 0.0;
+                    self.apply_music_volume();
+                    return;
+                }
+                Err(error) => eprintln!("could not load {}: {}", self.tracks[track].display(), error),
+            }
+        }
+    }
+
+    fn apply_music_volume(&mut self) {
+        let volume = match (self.music_muted, self.ducked_until) {
+            (true, _) => 0.0,
+            (false, Some(_)) => self.volumes.music * DUCKED_VOLUME,
+            (false, None) => self.volumes.music,
+        };
+
+        if let Some(music) = self.music.as_mut() {
+            self.device.set_music_volume(music, volume);
+        }
+    }
+
+    // the slide of a tile, or a thud when there was no tile to move
+    pub fn play_move(&mut self, moved: bool) {
+        self.device.play_sound(if moved { &self.slide } else { &self.thud });
+    }
+
+    pub fn play_win(&mut self) {
+        self.device.play_sound(&self.win);
+        self.duck_music();
+    }
+
+    pub fn play_personal_best(&mut self) {
+        self.device.play_sound(&self.personal_best);
+        self.duck_music();
+    }
+
+    // turns the music down for a moment, so the fanfare can be heard over it
+    fn duck_music(&mut self) {
+        self.ducked_until = Some(Instant::now() + DUCK_DURATION);
+        self.apply_music_volume();
+    }
+}
+
+impl Subscriber for Audio {
+    // replays being watched are quiet until the end
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        match event {
+            Event::MoveApplied { moved, .. } if game.playback.is_none() => self.play_move(moved),
+            // a new personal best gets a jingle of its own
+            Event::BoardSolved { personal_best: true } => self.play_personal_best(),
+            Event::BoardSolved { personal_best: false } => self.play_win(),
+            _ => {}
+        }
+    }
+}
+
+// sine notes one after another, each given as (frequency in hz, length in seconds)
+fn notes(notes: &[(f32, f32)]) -> Vec<i16> {
+    notes
+        .iter()
+        .flat_map(|(frequency, length)| {
+            let count = (length * SAMPLE_RATE as f32) as usize;
+
+            (0..count).map(move |i| {
+                let time = i as f32 / SAMPLE_RATE as f32;
+                // every note fades out, so they don't click where they meet
+                let envelope = 1.0 - i as f32 / count as f32;
+                ((time * frequency * TAU).sin() * envelope * 0.4 * i16::MAX as f32) as i16
+            })
+        })
+        .collect()
+}
+
+// a soft burst of noise, smoothed so it sounds more like a tile sliding than a hiss
+fn swoosh(length: f32) -> Vec<i16> {
+    let mut rng = rand::thread_rng();
+    let count = (length * SAMPLE_RATE as f32) as usize;
+    let mut smoothed = 0.0;
+
+    (0..count)
+        .map(|i| {
+            smoothed += (rng.gen_range(-1.0..1.0) - smoothed) * 0.15;
+            // quick to rise, slow to fade
+            let progress = i as f32 / count as f32;
+            let envelope = (progress * 10.0).min(1.0) * (1.0 - progress);
+            (smoothed * envelope * 0.6 * i16::MAX as f32) as i16
+        })
+        .collect()
+}
+
+// the music files in the folder, in alphabetical order
+fn find_tracks(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut tracks: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| MUSIC_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        })
+        .collect();
+
+    tracks.sort();
+    tracks
+}
+
+fn load(samples: &[i16]) -> Option<Sound> {
+    let wave = Wave::load_wave_from_mem(".wav", &wav_file(samples));
+    Sound::load_sound_from_wave(&wave).ok()
+}
+
+// a mono, 16 bit pcm wav file holding the samples
+fn wav_file(samples: &[i16]) -> Vec<u8> {
+    let data_size = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_size as usize);
+
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    // the format chunk: its size, pcm, 1 channel, the sample rate, bytes per second, bytes per
+    // sample and bits per sample
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    bytes
+}
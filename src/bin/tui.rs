@@ -0,0 +1,171 @@
+// the puzzle in the terminal, for playing over ssh or on machines without a gpu. it only needs the
+// library, none of the game's window, audio or config:
+// cargo run --bin tui -- --size 4
+use clap::Parser;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Print, Stylize};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use fifteen_puzzle_rust::{parse_board_size, Board, Difficulty, Direction};
+use rand::prelude::*;
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(version, about = "A sliding puzzle game in the terminal")]
+struct Args {
+    /// Board size, either <size> or <width>x<height>, each side from 3 to 10
+    #[arg(long, value_parser = parse_board_size, default_value = "4")]
+    size: (u8, u8),
+
+    /// How thoroughly the board is scrambled
+    #[arg(long, value_enum, default_value = "random")]
+    difficulty: Difficulty,
+
+    /// Seed for the first scramble, the same seed always gives the same board as in the window
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+struct Game {
+    board: Board,
+    seed: u64,
+    move_count: u32,
+    // the timer starts with the first move and stops once the board is solved
+    started: Option<Instant>,
+    solve_time: Option<Duration>,
+}
+
+impl Game {
+    fn new(width: u8, height: u8, difficulty: Difficulty, seed: Option<u64>) -> Game {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().gen_range(0..100_000_000));
+        let mut board = Board::solved(width, height);
+        if let Err(error) = board.scramble(difficulty, seed) {
+            eprintln!("could not scramble the board: {}", error);
+        }
+
+        Game {
+            board,
+            seed,
+            move_count: 0,
+            started: None,
+            solve_time: None,
+        }
+    }
+
+    fn move_empty(&mut self, direction: Direction) {
+        if self.board.solved || !self.board.move_empty(direction) {
+            return;
+        }
+
+        let started = *self.started.get_or_insert_with(Instant::now);
+        self.move_count += 1;
+
+        if self.board.solved {
+            self.solve_time = Some(started.elapsed());
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        match (self.solve_time, self.started) {
+            (Some(time), _) => time,
+            (None, Some(started)) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+}
+
+// the same format as the hud of the window, minutes:seconds.milliseconds
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    format!("{:02}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+fn draw(stdout: &mut Stdout, game: &Game) -> io::Result<()> {
+    let board = &game.board;
+    let empty_value = board.get_empty_value();
+    // every cell is as wide as the biggest number, with a space on each side
+    let cell_width = empty_value.to_string().len() + 2;
+    let border = "+".to_owned() + &"-".repeat(cell_width * board.width as usize) + "+";
+
+    queue!(stdout, Clear(ClearType::All), MoveTo(0, 0), Print(&border))?;
+
+    for (row, cells) in board.cells.chunks(board.width as usize).enumerate() {
+        queue!(stdout, MoveTo(0, row as u16 + 1), Print("|"))?;
+
+        for (column, cell) in cells.iter().enumerate() {
+            let text = if *cell == empty_value {
+                " ".repeat(cell_width)
+            } else {
+                format!("{:>width$} ", cell, width = cell_width - 1)
+            };
+
+            // tiles in their solved position stand out, like with the correctness colors
+            if *cell == (row * board.width as usize + column) as i32 + 1 {
+                queue!(stdout, Print(text.green()))?;
+            } else {
+                queue!(stdout, Print(text))?;
+            }
+        }
+
+        queue!(stdout, Print("|"))?;
+    }
+
+    let y = board.height as u16 + 1;
+    let status = format!("Moves: {}  Time: {}  Seed: {}", game.move_count, format_duration(game.elapsed()), game.seed);
+    queue!(stdout, MoveTo(0, y), Print(&border), MoveTo(0, y + 1), Print(status))?;
+
+    if board.solved {
+        queue!(stdout, MoveTo(0, y + 3), Print("You win!".bold()))?;
+    }
+
+    queue!(stdout, MoveTo(0, y + 4), Print("[arrows / hjkl] move  [n] new scramble  [q] quit"))?;
+    stdout.flush()
+}
+
+fn run(stdout: &mut Stdout, args: &Args) -> io::Result<()> {
+    let (width, height) = args.size;
+    let mut game = Game::new(width, height, args.difficulty, args.seed);
+
+    loop {
+        draw(stdout, &game)?;
+
+        // redrawn at least every 100ms, so the timer keeps counting
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => game.move_empty(Direction::Up),
+            KeyCode::Down | KeyCode::Char('j') => game.move_empty(Direction::Down),
+            KeyCode::Left | KeyCode::Char('h') => game.move_empty(Direction::Left),
+            KeyCode::Right | KeyCode::Char('l') => game.move_empty(Direction::Right),
+            KeyCode::Char('n') => game = Game::new(width, height, args.difficulty, None),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut stdout = io::stdout();
+
+    let result = terminal::enable_raw_mode()
+        .and_then(|_| execute!(stdout, EnterAlternateScreen, Hide))
+        .and_then(|_| run(&mut stdout, &args));
+
+    // the terminal is put back even if drawing failed, so it stays usable
+    let restored = execute!(stdout, Show, LeaveAlternateScreen).and_then(|_| terminal::disable_raw_mode());
+
+    if let Err(error) = result.and(restored) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
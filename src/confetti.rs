@@ -0,0 +1,133 @@
+use crate::events::{Event, EventBus, Subscriber};
+use crate::Game;
+use rand::prelude::*;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const PARTICLE_COUNT: usize = 150;
+// in pixels per second (squared)
+const GRAVITY: f32 = 900.0;
+const MIN_SPEED: f32 = 250.0;
+const MAX_SPEED: f32 = 750.0;
+// particles are gone after this many seconds, even if they are still on screen
+const LIFETIME: f32 = 3.0;
+const COLORS: [Color; 6] = [
+    Color::new(239, 71, 111, 255),
+    Color::new(255, 209, 102, 255),
+    Color::new(6, 214, 160, 255),
+    Color::new(17, 138, 178, 255),
+    Color::new(155, 93, 229, 255),
+    Color::new(255, 133, 51, 255),
+];
+
+struct Particle {
+    position: Vector2,
+    velocity: Vector2,
+    // in degrees, the pieces tumble as they fall
+    rotation: f32,
+    spin: f32,
+    size: Vector2,
+    color: Color,
+}
+
+// a burst of confetti for every solved board
+pub struct Celebration {
+    pub burst: Option<Confetti>,
+    // set once a board was solved, until the burst goes off on the next update
+    due: bool,
+    enabled: bool,
+}
+
+impl Celebration {
+    pub fn new(enabled: bool) -> Celebration {
+        Celebration {
+            burst: None,
+            due: false,
+            enabled,
+        }
+    }
+
+    // a burst that is due is shot up from x, y
+    pub fn update(&mut self, delta: f32, x: f32, y: f32, screen_height: f32) {
+        if std::mem::take(&mut self.due) {
+            self.burst = Some(Confetti::burst(x, y));
+        }
+
+        if let Some(burst) = self.burst.as_mut() {
+            burst.update(delta, screen_height);
+        }
+
+        if self.burst.as_ref().is_some_and(|burst| burst.is_finished()) {
+            self.burst = None;
+        }
+    }
+}
+
+impl Subscriber for Celebration {
+    fn on_event(&mut self, event: Event, _: &Game, _: &mut EventBus) {
+        if matches!(event, Event::BoardSolved { .. }) {
+            self.due = self.enabled;
+        }
+    }
+}
+
+// a burst of confetti, shot up from a point and falling back down
+pub struct Confetti {
+    particles: Vec<Particle>,
+    age: f32,
+}
+
+impl Confetti {
+    pub fn burst(x: f32, y: f32) -> Confetti {
+        let mut rng = rand::thread_rng();
+        let particles = (0..PARTICLE_COUNT)
+            .map(|_| {
+                // mostly upwards, spreading out to the sides
+                let angle = -PI / 2.0 + rng.gen_range(-0.9..0.9);
+                let speed = rng.gen_range(MIN_SPEED..MAX_SPEED);
+
+                Particle {
+                    position: Vector2::new(x, y),
+                    velocity: Vector2::new(angle.cos() * speed, angle.sin() * speed),
+                    rotation: rng.gen_range(0.0..360.0),
+                    spin: rng.gen_range(-720.0..720.0),
+                    size: Vector2::new(rng.gen_range(6.0..12.0), rng.gen_range(3.0..6.0)),
+                    color: COLORS[rng.gen_range(0..COLORS.len())],
+                }
+            })
+            .collect();
+
+        Confetti { particles, age: 0.0 }
+    }
+
+    // moves the particles on by delta seconds, dropping the ones that fell below the screen
+    pub fn update(&mut self, delta: f32, screen_height: f32) {
+        self.age += delta;
+
+        for particle in &mut self.particles {
+            particle.velocity.y += GRAVITY * delta;
+            // air resistance, so the pieces drift down rather than drop like stones
+            particle.velocity.x *= 1.0 - 1.5 * delta;
+            particle.position.x += particle.velocity.x * delta;
+            particle.position.y += particle.velocity.y * delta;
+            particle.rotation += particle.spin * delta;
+        }
+
+        self.particles.retain(|particle| particle.position.y < screen_height + 20.0);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.particles.is_empty() || self.age >= LIFETIME
+    }
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle) {
+        // fading out over the last second
+        let alpha = (LIFETIME - self.age).clamp(0.0, 1.0);
+
+        for particle in &self.particles {
+            let rec = Rectangle::new(particle.position.x, particle.position.y, particle.size.x, particle.size.y);
+            let origin = Vector2::new(particle.size.x / 2.0, particle.size.y / 2.0);
+            d.draw_rectangle_pro(rec, origin, particle.rotation, particle.color.fade(alpha));
+        }
+    }
+}
@@ -0,0 +1,744 @@
+use crate::solver::SolverHeuristic;
+use crate::storage;
+use crate::Difficulty;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+// user preferences, loaded from ~/.config/fifteen-puzzle/config.toml (or the platform equivalent).
+// every field is optional in the file, anything missing falls back to the default
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub width: u8,
+    pub height: u8,
+    pub difficulty: Difficulty,
+    pub fps: u32,
+    // the master volume, the music and sound effects have their own on top of it
+    pub volume: f32,
+    pub muted: bool,
+    pub effects_volume: f32,
+    // background music is played from the .ogg and .mp3 files in this folder
+    pub music_dir: PathBuf,
+    pub music_volume: f32,
+    pub music_muted: bool,
+    // zen mode plays the music from this folder instead, if it is set
+    pub zen_music_dir: Option<PathBuf>,
+    // the pause between the moves played back by auto-solve
+    pub auto_solve_delay_ms: u64,
+    pub solver_heuristic: SolverHeuristic,
+    // race against the best previous solve when playing a scramble again
+    pub ghost_race: bool,
+    // race the solver on a second board, which plays this many moves per second
+    pub solver_race: bool,
+    pub solver_race_moves_per_second: f32,
+    // set while racing someone online with --host or --join, their board is shown next to yours
+    #[serde(skip)]
+    pub online_race: bool,
+    // set while two players race at the same keyboard, the w a s d keys are the second player's then
+    #[serde(skip)]
+    pub versus: bool,
+    // a wca style inspection before every timed solve: the scrambled board can be looked at for this
+    // long but not moved, then the timer starts by itself
+    pub inspection: bool,
+    pub inspection_seconds: u64,
+    // timed solves start like on a stackmat: the continue key is held until the light turns green,
+    // and the timer starts when it is let go, rather than with the first move
+    pub stackmat: bool,
+    pub stackmat_hold_ms: u64,
+    // how long boards of the blindfold mode are shown before their numbers are hidden
+    pub blindfold_memorize_seconds: u64,
+    // how many moves from the empty cell tiles can be seen in fog of war
+    pub fog_radius: u8,
+    // the time a time attack starts with, and the time every board solved adds to it
+    pub time_attack_seconds: u64,
+    pub time_attack_bonus_seconds: u64,
+    // boards solved within this time keep the streak of the streak mode going
+    pub streak_target_seconds: u64,
+    // the words the tiles of the letters mode spell row by row, the first letter on the first tile
+    pub letter_words: Vec<String>,
+    // how long twitch chat votes on each move when it plays the board with --twitch
+    pub twitch_vote_seconds: u64,
+    // the splits of every phase of a timed solve in the corner of the board, against the best ones
+    pub show_splits: bool,
+    // solves are only sent to an online leaderboard once a server is set here
+    pub online_leaderboard_url: Option<String>,
+    // turns off every animation and effect, tiles jump straight to their new cell
+    pub reduced_motion: bool,
+    // new levels are shown being scrambled, the next level key skips it
+    pub animate_scramble: bool,
+    // how fast tiles slide and scrambles play, 2 is twice as fast
+    pub animation_speed: f32,
+    // shows the solved board faded underneath the tiles, to see where each of them goes
+    pub goal_overlay: bool,
+    // the arrow keys, the d-pad and swipes slide a tile into the empty cell rather than moving the
+    // empty cell, so they go the other way
+    pub move_tiles: bool,
+    // holding a movement key moves again after the delay, and then again at every interval
+    pub key_repeat: bool,
+    pub key_repeat_delay_ms: u64,
+    pub key_repeat_interval_ms: u64,
+    // tints tiles by whether they are in their solved position, in colors that can be told apart
+    // with the given kind of color blindness. the symbols mark them with a check or a cross too
+    pub correctness_colors: CorrectnessColors,
+    pub correctness_symbols: bool,
+    // a .ttf file to draw text with instead of raylib's pixel font
+    pub font: Option<PathBuf>,
+    // a picture to cut into tiles instead of numbering them, with their numbers in a corner if set
+    pub image: Option<PathBuf>,
+    pub image_numbers: bool,
+    // a small picture of the solved board in the corner of image puzzles
+    pub image_thumbnail: bool,
+    pub window: WindowConfig,
+    // fullscreen covers the monitor with a window without borders instead of switching the
+    // monitor over to the game, which is quicker to switch in and out of
+    pub borderless_fullscreen: bool,
+    // renders at the full resolution of high dpi (retina, 4k) screens rather than scaling up a
+    // smaller picture, which looks blurry
+    pub high_dpi: bool,
+    // a built-in theme or one from the themes folder to use instead of the colors below
+    pub theme: Option<String>,
+    pub colors: Colors,
+    pub keybinds: Keybinds,
+    pub gamepad: GamepadConfig,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            width: 4,
+            height: 4,
+            difficulty: Difficulty::Random,
+            fps: 30,
+            volume: 1.0,
+            muted: false,
+            effects_volume: 1.0,
+            music_dir: PathBuf::from("assets/music"),
+            music_volume: 0.5,
+            music_muted: false,
+            zen_music_dir: None,
+            auto_solve_delay_ms: 200,
+            solver_heuristic: SolverHeuristic::PatternDatabase,
+            ghost_race: true,
+            solver_race: false,
+            online_race: false,
+            versus: false,
+            inspection: false,
+            inspection_seconds: 15,
+            stackmat: false,
+            stackmat_hold_ms: 550,
+            solver_race_moves_per_second: 2.0,
+            blindfold_memorize_seconds: 15,
+            fog_radius: 2,
+            time_attack_seconds: 120,
+            time_attack_bonus_seconds: 30,
+            streak_target_seconds: 60,
+            letter_words: ["RATE", "YOUR", "MIND", "PAL"].map(String::from).to_vec(),
+            twitch_vote_seconds: 10,
+            show_splits: true,
+            online_leaderboard_url: None,
+            reduced_motion: false,
+            animate_scramble: true,
+            animation_speed: 1.0,
+            goal_overlay: false,
+            move_tiles: false,
+            key_repeat: true,
+            key_repeat_delay_ms: 250,
+            key_repeat_interval_ms: 60,
+            correctness_colors: CorrectnessColors::Off,
+            correctness_symbols: false,
+            font: None,
+            image: None,
+            image_numbers: false,
+            image_thumbnail: true,
+            window: WindowConfig::default(),
+            borderless_fullscreen: false,
+            high_dpi: true,
+            theme: None,
+            colors: Colors::default(),
+            keybinds: Keybinds::default(),
+            gamepad: GamepadConfig::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    // the preferred size of a cell, the board is scaled to fit between min and max board pixels.
+    // this sizes the window when it opens, after that the board is fit to the window as it is resized
+    pub cell_size: i32,
+    pub min_board_pixels: i32,
+    pub max_board_pixels: i32,
+    pub hud_height: i32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> WindowConfig {
+        WindowConfig {
+            cell_size: 120,
+            min_board_pixels: 480,
+            max_board_pixels: 720,
+            hud_height: 40,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Colors {
+    #[serde(with = "hex_color")]
+    pub background_darker: Color,
+    #[serde(with = "hex_color")]
+    pub background: Color,
+    #[serde(with = "hex_color")]
+    pub background_lighter: Color,
+    #[serde(with = "hex_color")]
+    pub text: Color,
+    #[serde(with = "hex_color")]
+    pub border: Color,
+    // the width of the lines around tiles, and whether their numbers are drawn bold
+    pub tile_border_width: f32,
+    pub bold_numbers: bool,
+}
+
+impl Default for Colors {
+    fn default() -> Colors {
+        Colors {
+            background_darker: Color::new(11, 11, 11, 255),
+            background: Color::new(22, 22, 22, 255),
+            background_lighter: Color::new(55, 55, 55, 255),
+            text: Color::new(240, 240, 240, 255),
+            border: Color::new(230, 230, 230, 255),
+            tile_border_width: 1.0,
+            bold_numbers: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BuiltinTheme {
+    Dark,
+    Light,
+    Solarized,
+    HighContrast,
+}
+
+impl BuiltinTheme {
+    pub const ALL: [BuiltinTheme; 4] = [
+        BuiltinTheme::Dark,
+        BuiltinTheme::Light,
+        BuiltinTheme::Solarized,
+        BuiltinTheme::HighContrast,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            BuiltinTheme::Dark => "dark",
+            BuiltinTheme::Light => "light",
+            BuiltinTheme::Solarized => "solarized",
+            BuiltinTheme::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltinTheme::Dark => "Dark",
+            BuiltinTheme::Light => "Light",
+            BuiltinTheme::Solarized => "Solarized",
+            BuiltinTheme::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn colors(&self) -> Colors {
+        match self {
+            BuiltinTheme::Dark => Colors::default(),
+            BuiltinTheme::Light => Colors {
+                background_darker: Color::new(200, 200, 200, 255),
+                background: Color::new(245, 245, 245, 255),
+                background_lighter: Color::new(225, 225, 225, 255),
+                text: Color::new(20, 20, 20, 255),
+                border: Color::new(40, 40, 40, 255),
+                ..Colors::default()
+            },
+            // the dark solarized palette by Ethan Schoonover
+            BuiltinTheme::Solarized => Colors {
+                background_darker: Color::new(0, 43, 54, 255),
+                background: Color::new(7, 54, 66, 255),
+                background_lighter: Color::new(88, 110, 117, 255),
+                text: Color::new(238, 232, 213, 255),
+                border: Color::new(147, 161, 161, 255),
+                ..Colors::default()
+            },
+            // for low vision, only black and white. the tiles stand out from the empty cell with
+            // their thick borders
+            BuiltinTheme::HighContrast => Colors {
+                background_darker: Color::new(0, 0, 0, 255),
+                background: Color::new(0, 0, 0, 255),
+                background_lighter: Color::new(0, 0, 0, 255),
+                text: Color::new(255, 255, 255, 255),
+                border: Color::new(255, 255, 255, 255),
+                tile_border_width: 6.0,
+                bold_numbers: true,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorrectnessColors {
+    Off,
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl CorrectnessColors {
+    pub const ALL: [CorrectnessColors; 5] = [
+        CorrectnessColors::Off,
+        CorrectnessColors::Standard,
+        CorrectnessColors::Deuteranopia,
+        CorrectnessColors::Protanopia,
+        CorrectnessColors::Tritanopia,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CorrectnessColors::Off => "off",
+            CorrectnessColors::Standard => "green / red",
+            CorrectnessColors::Deuteranopia => "deuteranopia",
+            CorrectnessColors::Protanopia => "protanopia",
+            CorrectnessColors::Tritanopia => "tritanopia",
+        }
+    }
+
+    // the tints of tiles in their solved position and of misplaced ones. the color blind palettes
+    // are picked from the Okabe-Ito colors
+    pub fn tints(&self) -> Option<(Color, Color)> {
+        match self {
+            CorrectnessColors::Off => None,
+            CorrectnessColors::Standard => Some((Color::new(67, 160, 71, 255), Color::new(229, 57, 53, 255))),
+            // red and green look alike, blue and orange don't
+            CorrectnessColors::Deuteranopia | CorrectnessColors::Protanopia => {
+                Some((Color::new(0, 114, 178, 255), Color::new(230, 159, 0, 255)))
+            }
+            // blue and green look alike, as do yellow and violet, but red and sky blue don't
+            CorrectnessColors::Tritanopia => Some((Color::new(86, 180, 233, 255), Color::new(213, 94, 0, 255))),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybinds {
+    // the w a s d, the h j k l and the numpad 8 4 2 6 keys move as well as the movement keybinds.
+    // while they are turned on, anything else bound to these keys is ignored
+    pub wasd: bool,
+    pub vim: bool,
+    pub numpad: bool,
+    #[serde(with = "key_list")]
+    pub up: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub down: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub left: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub right: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub undo: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub redo: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub settings: Vec<KeyboardKey>,
+    #[serde(with = "key_list", rename = "continue")]
+    pub next_level: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub copy_seed: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub copy_moves: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub daily: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub hint: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub auto_solve: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub watch_replay: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub leaderboard: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub statistics: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub export_statistics: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub next_track: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub mute_music: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub mute: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub audio_settings: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub next_theme: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub thumbnail: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub goal_overlay: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub fullscreen: Vec<KeyboardKey>,
+    #[serde(with = "key_list")]
+    pub screenshot: Vec<KeyboardKey>,
+    // a new scramble of the board being played
+    #[serde(with = "key_list")]
+    pub scramble: Vec<KeyboardKey>,
+    // the same scramble again, from the start
+    #[serde(with = "key_list")]
+    pub retry: Vec<KeyboardKey>,
+    // a summary of the solve to paste into chats
+    #[serde(with = "key_list")]
+    pub share: Vec<KeyboardKey>,
+    // which of two empty cells moves
+    #[serde(with = "key_list")]
+    pub switch_blank: Vec<KeyboardKey>,
+}
+
+impl Default for Keybinds {
+    fn default() -> Keybinds {
+        Keybinds {
+            wasd: false,
+            vim: false,
+            numpad: true,
+            up: vec![KeyboardKey::KEY_UP],
+            down: vec![KeyboardKey::KEY_DOWN],
+            left: vec![KeyboardKey::KEY_LEFT],
+            right: vec![KeyboardKey::KEY_RIGHT],
+            undo: vec![KeyboardKey::KEY_Z],
+            redo: vec![KeyboardKey::KEY_Y],
+            settings: vec![KeyboardKey::KEY_TAB],
+            next_level: vec![KeyboardKey::KEY_SPACE],
+            copy_seed: vec![KeyboardKey::KEY_C],
+            copy_moves: vec![KeyboardKey::KEY_E],
+            daily: vec![KeyboardKey::KEY_D],
+            hint: vec![KeyboardKey::KEY_H],
+            auto_solve: vec![KeyboardKey::KEY_A],
+            watch_replay: vec![KeyboardKey::KEY_V],
+            leaderboard: vec![KeyboardKey::KEY_L],
+            statistics: vec![KeyboardKey::KEY_S],
+            export_statistics: vec![KeyboardKey::KEY_X],
+            next_track: vec![KeyboardKey::KEY_N],
+            mute_music: vec![KeyboardKey::KEY_B],
+            mute: vec![KeyboardKey::KEY_M],
+            audio_settings: vec![KeyboardKey::KEY_O],
+            next_theme: vec![KeyboardKey::KEY_T],
+            thumbnail: vec![KeyboardKey::KEY_P],
+            goal_overlay: vec![KeyboardKey::KEY_G],
+            fullscreen: vec![KeyboardKey::KEY_F11],
+            screenshot: vec![KeyboardKey::KEY_F12],
+            scramble: vec![KeyboardKey::KEY_KP_5],
+            retry: vec![KeyboardKey::KEY_R],
+            share: vec![KeyboardKey::KEY_I],
+            switch_blank: vec![KeyboardKey::KEY_Q],
+        }
+    }
+}
+
+// the first controller plugged in. the d-pad and the left stick move, the buttons are written by
+// name, e.g. "A" or "START"
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    // how far the stick has to be pushed to move, from 0 to 1
+    pub deadzone: f32,
+    #[serde(with = "button_name")]
+    pub up: GamepadButton,
+    #[serde(with = "button_name")]
+    pub down: GamepadButton,
+    #[serde(with = "button_name")]
+    pub left: GamepadButton,
+    #[serde(with = "button_name")]
+    pub right: GamepadButton,
+    // picks menu items and continues to the next level
+    #[serde(with = "button_name")]
+    pub confirm: GamepadButton,
+    #[serde(with = "button_name")]
+    pub pause: GamepadButton,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> GamepadConfig {
+        GamepadConfig {
+            deadzone: 0.5,
+            up: GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP,
+            down: GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN,
+            left: GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT,
+            right: GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT,
+            confirm: GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+            pause: GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT,
+        }
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join("fifteen-puzzle").join("config.toml"))
+}
+
+// loads the config file, writing the defaults if it does not exist yet.
+// a broken config file is reported and ignored rather than overwritten
+pub fn load() -> Config {
+    let path = match config_path() {
+        Some(path) => path,
+        None => return Config::default(),
+    };
+
+    if !storage::exists(&path) {
+        let config = Config::default();
+
+        if let Err(error) = save(&config) {
+            eprintln!("could not write default config: {}", error);
+        }
+
+        return config;
+    }
+
+    let result = storage::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| toml::from_str(&text).map_err(|error| error.to_string()));
+
+    match result {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("invalid config file {}: {}", path.display(), error);
+            Config::default()
+        }
+    }
+}
+
+pub fn save(config: &Config) -> Result<(), String> {
+    let path = config_path().ok_or("no config directory on this platform")?;
+    let text = toml::to_string_pretty(config).map_err(|error| error.to_string())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+
+    storage::write(&path, &text).map_err(|error| error.to_string())
+}
+
+// changes settings of the config file from within the game. the file is loaded again rather than
+// saving the running config, which holds the overrides from the command line
+pub fn update(change: impl FnOnce(&mut Config)) -> Result<(), String> {
+    let mut config = load();
+    change(&mut config);
+    save(&config)
+}
+
+// colors are written as "#rrggbb" or "#rrggbbaa"
+mod hex_color {
+    use raylib::prelude::Color;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex = if color.a == 255 {
+            format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", color.r, color.g, color.b, color.a)
+        };
+
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse(&text).ok_or_else(|| de::Error::custom(format!("invalid color: {}", text)))
+    }
+
+    pub fn parse(text: &str) -> Option<Color> {
+        let hex = text.strip_prefix('#')?;
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+        match hex.len() {
+            6 => Some(Color::new(channel(0)?, channel(2)?, channel(4)?, 255)),
+            8 => Some(Color::new(channel(0)?, channel(2)?, channel(4)?, channel(6)?)),
+            _ => None,
+        }
+    }
+}
+
+// keys are written by name, e.g. "UP", "SPACE" or "Z"
+pub mod key_name {
+    use raylib::prelude::KeyboardKey;
+
+    const KEY_NAMES: [(&str, KeyboardKey); 74] = [
+        ("UP", KeyboardKey::KEY_UP),
+        ("DOWN", KeyboardKey::KEY_DOWN),
+        ("LEFT", KeyboardKey::KEY_LEFT),
+        ("RIGHT", KeyboardKey::KEY_RIGHT),
+        ("SPACE", KeyboardKey::KEY_SPACE),
+        ("ENTER", KeyboardKey::KEY_ENTER),
+        ("TAB", KeyboardKey::KEY_TAB),
+        ("BACKSPACE", KeyboardKey::KEY_BACKSPACE),
+        ("ESCAPE", KeyboardKey::KEY_ESCAPE),
+        ("HOME", KeyboardKey::KEY_HOME),
+        ("END", KeyboardKey::KEY_END),
+        ("PAGE_UP", KeyboardKey::KEY_PAGE_UP),
+        ("PAGE_DOWN", KeyboardKey::KEY_PAGE_DOWN),
+        ("F1", KeyboardKey::KEY_F1),
+        ("F2", KeyboardKey::KEY_F2),
+        ("F3", KeyboardKey::KEY_F3),
+        ("F4", KeyboardKey::KEY_F4),
+        ("F5", KeyboardKey::KEY_F5),
+        ("F6", KeyboardKey::KEY_F6),
+        ("F7", KeyboardKey::KEY_F7),
+        ("F8", KeyboardKey::KEY_F8),
+        ("F9", KeyboardKey::KEY_F9),
+        ("F10", KeyboardKey::KEY_F10),
+        ("F11", KeyboardKey::KEY_F11),
+        ("F12", KeyboardKey::KEY_F12),
+        ("0", KeyboardKey::KEY_ZERO),
+        ("1", KeyboardKey::KEY_ONE),
+        ("2", KeyboardKey::KEY_TWO),
+        ("3", KeyboardKey::KEY_THREE),
+        ("4", KeyboardKey::KEY_FOUR),
+        ("5", KeyboardKey::KEY_FIVE),
+        ("6", KeyboardKey::KEY_SIX),
+        ("7", KeyboardKey::KEY_SEVEN),
+        ("8", KeyboardKey::KEY_EIGHT),
+        ("9", KeyboardKey::KEY_NINE),
+        ("A", KeyboardKey::KEY_A),
+        ("B", KeyboardKey::KEY_B),
+        ("C", KeyboardKey::KEY_C),
+        ("D", KeyboardKey::KEY_D),
+        ("E", KeyboardKey::KEY_E),
+        ("F", KeyboardKey::KEY_F),
+        ("G", KeyboardKey::KEY_G),
+        ("H", KeyboardKey::KEY_H),
+        ("I", KeyboardKey::KEY_I),
+        ("J", KeyboardKey::KEY_J),
+        ("K", KeyboardKey::KEY_K),
+        ("L", KeyboardKey::KEY_L),
+        ("M", KeyboardKey::KEY_M),
+        ("N", KeyboardKey::KEY_N),
+        ("O", KeyboardKey::KEY_O),
+        ("P", KeyboardKey::KEY_P),
+        ("Q", KeyboardKey::KEY_Q),
+        ("R", KeyboardKey::KEY_R),
+        ("S", KeyboardKey::KEY_S),
+        ("T", KeyboardKey::KEY_T),
+        ("U", KeyboardKey::KEY_U),
+        ("V", KeyboardKey::KEY_V),
+        ("W", KeyboardKey::KEY_W),
+        ("X", KeyboardKey::KEY_X),
+        ("Y", KeyboardKey::KEY_Y),
+        ("Z", KeyboardKey::KEY_Z),
+        ("LEFT_SHIFT", KeyboardKey::KEY_LEFT_SHIFT),
+        ("LEFT_CONTROL", KeyboardKey::KEY_LEFT_CONTROL),
+        ("LEFT_ALT", KeyboardKey::KEY_LEFT_ALT),
+        ("KP_0", KeyboardKey::KEY_KP_0),
+        ("KP_1", KeyboardKey::KEY_KP_1),
+        ("KP_2", KeyboardKey::KEY_KP_2),
+        ("KP_3", KeyboardKey::KEY_KP_3),
+        ("KP_4", KeyboardKey::KEY_KP_4),
+        ("KP_5", KeyboardKey::KEY_KP_5),
+        ("KP_6", KeyboardKey::KEY_KP_6),
+        ("KP_7", KeyboardKey::KEY_KP_7),
+        ("KP_8", KeyboardKey::KEY_KP_8),
+        ("KP_9", KeyboardKey::KEY_KP_9),
+    ];
+
+    pub fn name_of(key: KeyboardKey) -> &'static str {
+        KEY_NAMES
+            .iter()
+            .find(|(_, named_key)| *named_key == key)
+            .map(|(name, _)| *name)
+            .unwrap_or("NULL")
+    }
+
+    pub fn parse(name: &str) -> Option<KeyboardKey> {
+        KEY_NAMES
+            .iter()
+            .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+            .map(|(_, key)| *key)
+    }
+}
+
+// a keybind is one key name or a list of them, e.g. "UP" or ["UP", "W"]. a single key is written
+// by itself, the way keybinds were written before they could have more than one key
+mod key_list {
+    use super::key_name;
+    use raylib::prelude::KeyboardKey;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Names {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(keys: &[KeyboardKey], serializer: S) -> Result<S::Ok, S::Error> {
+        if let [key] = keys {
+            return serializer.serialize_str(key_name::name_of(*key));
+        }
+
+        serializer.collect_seq(keys.iter().map(|key| key_name::name_of(*key)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<KeyboardKey>, D::Error> {
+        let names = match Names::deserialize(deserializer)? {
+            Names::One(name) => vec![name],
+            Names::Many(names) => names,
+        };
+
+        names
+            .iter()
+            .map(|name| key_name::parse(name).ok_or_else(|| de::Error::custom(format!("unknown key: {}", name))))
+            .collect()
+    }
+}
+
+// the buttons by the names of an xbox controller, which raylib maps every controller to
+mod button_name {
+    use raylib::prelude::GamepadButton;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    const BUTTON_NAMES: [(&str, GamepadButton); 16] = [
+        ("DPAD_UP", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP),
+        ("DPAD_DOWN", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN),
+        ("DPAD_LEFT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT),
+        ("DPAD_RIGHT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT),
+        ("A", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+        ("B", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT),
+        ("X", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT),
+        ("Y", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP),
+        ("LB", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1),
+        ("LT", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2),
+        ("RB", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1),
+        ("RT", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2),
+        ("SELECT", GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT),
+        ("START", GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+        ("LEFT_STICK", GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB),
+        ("RIGHT_STICK", GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB),
+    ];
+
+    pub fn serialize<S: Serializer>(button: &GamepadButton, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = BUTTON_NAMES
+            .iter()
+            .find(|(_, named_button)| named_button == button)
+            .map_or("UNKNOWN", |(name, _)| *name);
+
+        serializer.serialize_str(name)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GamepadButton, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        BUTTON_NAMES
+            .iter()
+            .find(|(button_name, _)| button_name.eq_ignore_ascii_case(&name))
+            .map(|(_, button)| *button)
+            .ok_or_else(|| de::Error::custom(format!("unknown button: {}", name)))
+    }
+}
@@ -0,0 +1,113 @@
+use crate::storage;
+use crate::Difficulty;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// everyone plays the same board on the same day, so the size and difficulty are fixed
+pub const WIDTH: u8 = 4;
+pub const HEIGHT: u8 = 4;
+pub const DIFFICULTY: Difficulty = Difficulty::Random;
+
+// the current utc day, counted in days since the unix epoch
+pub fn today() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    (seconds / 86400) as i64
+}
+
+// converts a day number to a (year, month, day) date,
+// see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_date(day: i64) -> (i64, u32, u32) {
+    let z = day + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day_of_month)
+}
+
+// the seed is the date written as yyyymmdd, so the daily board can also be shared with --seed
+pub fn seed(day: i64) -> u64 {
+    let (year, month, day_of_month) = civil_date(day);
+    (year * 10000 + month as i64 * 100 + day_of_month as i64) as u64
+}
+
+pub fn format_date(day: i64) -> String {
+    let (year, month, day_of_month) = civil_date(day);
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct DailyRecord {
+    last_completed: Option<i64>,
+    streak: u32,
+}
+
+impl DailyRecord {
+    pub fn is_completed(&self, day: i64) -> bool {
+        self.last_completed.is_some_and(|last| last >= day)
+    }
+
+    // the streak is kept until a whole day is missed
+    pub fn current_streak(&self, today: i64) -> u32 {
+        match self.last_completed {
+            Some(last) if last >= today - 1 => self.streak,
+            _ => 0,
+        }
+    }
+
+    pub fn complete(&mut self, day: i64) {
+        if self.is_completed(day) {
+            return;
+        }
+
+        self.streak = self.current_streak(day) + 1;
+        self.last_completed = Some(day);
+    }
+}
+
+// e.g. ~/.local/share/fifteen-puzzle/daily.json on linux
+pub fn record_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("daily.json"))
+}
+
+// a missing record means no daily puzzle was completed yet
+pub fn load() -> DailyRecord {
+    let path = match record_path().filter(|path| storage::exists(path)) {
+        Some(path) => path,
+        None => return DailyRecord::default(),
+    };
+
+    let result = storage::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|error| error.to_string()));
+
+    match result {
+        Ok(record) => record,
+        Err(error) => {
+            eprintln!("invalid daily record {}: {}", path.display(), error);
+            DailyRecord::default()
+        }
+    }
+}
+
+pub fn save(record: &DailyRecord) -> Result<(), String> {
+    let path = record_path().ok_or("no data directory on this platform")?;
+    let json = serde_json::to_string_pretty(record).map_err(|error| error.to_string())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("could not create {}: {}", parent.display(), error))?;
+    }
+
+    storage::write(&path, &json).map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
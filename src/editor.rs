@@ -0,0 +1,139 @@
+use crate::config::Config;
+use crate::{font, Board, Layout};
+use raylib::prelude::*;
+
+// what the editor asks the game to do
+pub enum EditorAction {
+    // play the position, or have the solver solve it if the bool is set
+    Play(Board, bool),
+    Back,
+}
+
+// arranging the tiles of a board by hand, to play or solve exactly that position. two tiles are
+// swapped by clicking one and then the other, or a whole position is typed in
+pub struct Editor {
+    pub board: Board,
+    // the tile clicked first, it is swapped with the next one clicked
+    selected: Option<usize>,
+    // the position being typed, the numbers row by row with _ or 0 for the empty cell
+    text: String,
+    // why the typed position couldn't be used
+    error: Option<String>,
+}
+
+impl Editor {
+    // starts from the solved board
+    pub fn new(width: u8, height: u8) -> Editor {
+        Editor {
+            board: Board::solved(width, height),
+            selected: None,
+            text: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn update(&mut self, rl: &mut RaylibHandle, layout: Layout) -> Option<EditorAction> {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return Some(EditorAction::Back);
+        }
+
+        while let Some(char) = rl.get_char_pressed() {
+            if char.is_ascii_digit() || char == ' ' || char == ',' || char == '_' {
+                self.text.push(char);
+                self.error = None;
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            self.text.pop();
+            self.error = None;
+        }
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            if let Some(index) = layout.get_index_at(&self.board, rl.get_mouse_position()) {
+                match self.selected.take() {
+                    Some(selected) => {
+                        self.board.cells.swap(selected, index);
+                        self.board.check_solved();
+                    }
+                    None => self.selected = Some(index),
+                }
+            }
+        }
+
+        if !rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            return None;
+        }
+
+        // enter puts a typed position on the board, and plays the board once nothing is typed
+        if !self.text.is_empty() {
+            match self.typed_cells().and_then(|cells| self.board.set_cells(cells)) {
+                Ok(()) => {
+                    self.text.clear();
+                    self.selected = None;
+                }
+                Err(error) => self.error = Some(error),
+            }
+
+            return None;
+        }
+
+        // an unsolvable position could never be finished, and a solved one is over already
+        if !self.board.is_solvable() || self.board.solved {
+            return None;
+        }
+
+        let solve = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+        Some(EditorAction::Play(self.board.clone(), solve))
+    }
+
+    fn typed_cells(&self) -> Result<Vec<i32>, String> {
+        let empty_value = self.board.get_empty_value();
+
+        self.text
+            .split([' ', ','])
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| match cell {
+                "_" | "0" => Ok(empty_value),
+                _ => cell.parse::<i32>().map_err(|_| format!("invalid cell: {}", cell)),
+            })
+            .collect()
+    }
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle, layout: Layout, config: &Config) {
+        let colors = &config.colors;
+        let screen_width = d.get_screen_width();
+
+        if let Some(selected) = self.selected {
+            let (x, y) = layout.cell_position(selected, self.board.width);
+            let bounds = Rectangle::new(x as f32, y as f32, layout.cell_size as f32, layout.cell_size as f32);
+            d.draw_rectangle_lines_ex(bounds, 4.0, colors.text);
+        }
+
+        d.draw_rectangle(0, 0, screen_width, 30, colors.background.fade(0.9));
+        let help = "[click] swap tiles  [ENTER] play  [SHIFT+ENTER] solve  [ESC] back";
+        font::draw_text(d, help, 10, 6, 20, colors.text);
+
+        // the editor's line takes the place of the hud
+        let y = d.get_screen_height() - config.window.hud_height;
+        d.draw_rectangle(0, y, screen_width, config.window.hud_height, colors.background);
+        d.draw_line(0, y, screen_width, y, colors.border);
+
+        let text = match (&self.error, self.text.is_empty()) {
+            (Some(error), _) => error.clone(),
+            (None, false) => "Position: ".to_owned() + &self.text + "_",
+            (None, true) => "Type a position, e.g. 1 2 3 4 _ 6".to_owned(),
+        };
+        font::draw_text(d, &text, 10, y + 10, 20, colors.text);
+
+        let status = if self.board.solved {
+            "Solved"
+        } else if self.board.is_solvable() {
+            "Solvable"
+        } else {
+            "Unsolvable"
+        };
+        let status_x = screen_width - font::measure_text(status, 20) - 10;
+        font::draw_text(d, status, status_x, y + 10, 20, colors.text);
+    }
+}
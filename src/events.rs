@@ -0,0 +1,71 @@
+use crate::{Direction, Game};
+use std::collections::VecDeque;
+
+// what happens in a game that other parts of it react to. events are published where they happen
+// and handed to every subscriber once a frame, rather than each place that e.g. makes a move also
+// having to play its sound
+#[derive(Clone, Copy, PartialEq)]
+pub enum Event {
+    // the player asked to slide the empty cell, the move is made once the events are dispatched
+    MoveRequested(Direction),
+    // a move of one or more tiles, or one rejected at the edge of the board. solved is set for the
+    // move that solved the board
+    MoveApplied { moved: bool, solved: bool },
+    // published once the solve is recorded, so it is known whether it was a personal best
+    BoardSolved { personal_best: bool },
+    ScrambleStarted,
+}
+
+pub trait Subscriber {
+    // events published in reaction, e.g. BoardSolved once a solve is recorded, are handed out after
+    // the ones already waiting
+    fn on_event(&mut self, event: Event, game: &Game, events: &mut EventBus);
+}
+
+// for the subscribers that aren't always there, e.g. the audio without an audio device
+impl<S: Subscriber> Subscriber for Option<S> {
+    fn on_event(&mut self, event: Event, game: &Game, events: &mut EventBus) {
+        if let Some(subscriber) = self {
+            subscriber.on_event(event, game, events);
+        }
+    }
+}
+
+pub struct EventBus {
+    queue: VecDeque<Event>,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus { queue: VecDeque::new() }
+    }
+
+    pub fn publish(&mut self, event: Event) {
+        self.queue.push_back(event);
+    }
+
+    // the oldest event that wasn't dispatched yet. events published while dispatching come after
+    // the ones already waiting
+    pub fn next(&mut self) -> Option<Event> {
+        self.queue.pop_front()
+    }
+}
+
+// hands the events of the frame to every subscriber, in the order they happened. the game makes the
+// moves asked for itself before the subscribers get to see them
+pub fn dispatch(game: &mut Game, subscribers: &mut [&mut dyn Subscriber]) {
+    while let Some(event) = game.events.next() {
+        // moves queued in the same frame after the one that solved the board are dropped by the game
+        if let Event::MoveRequested(direction) = event {
+            game.move_empty(direction);
+        }
+
+        let mut published = EventBus::new();
+
+        for subscriber in subscribers.iter_mut() {
+            subscriber.on_event(event, game, &mut published);
+        }
+
+        game.events.queue.append(&mut published.queue);
+    }
+}
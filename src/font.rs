@@ -0,0 +1,47 @@
+use raylib::prelude::*;
+use std::cell::RefCell;
+use std::path::Path;
+
+// the font is rendered this big and scaled down to the size text is drawn at, so it stays crisp
+// on big tiles. high dpi screens get it bigger still by their scale factor
+const LOAD_SIZE: i32 = 128;
+
+// every piece of text goes through here, so the font is kept here rather than passed along to
+// every draw function. None draws with raylib's default font
+thread_local! {
+    static FONT: RefCell<Option<Font>> = const { RefCell::new(None) };
+}
+
+// loads the font set with `font` in the config, text is drawn with it from then on
+pub fn load(rl: &mut RaylibHandle, thread: &RaylibThread, path: &Path) -> Result<(), String> {
+    let load_size = (LOAD_SIZE as f32 * rl.get_window_scale_dpi().x.max(1.0)) as i32;
+    let mut font = rl.load_font_ex(thread, &path.to_string_lossy(), load_size, FontLoadEx::Default(0))?;
+
+    // mipmaps keep the glyphs smooth when they are scaled down a lot, e.g. for the hud
+    unsafe {
+        raylib::ffi::GenTextureMipmaps(&mut font.texture);
+        raylib::ffi::SetTextureFilter(font.texture, TextureFilter::TEXTURE_FILTER_TRILINEAR as i32);
+    }
+
+    FONT.with(|cell| *cell.borrow_mut() = Some(font));
+    Ok(())
+}
+
+// the same spacing between characters as raylib's draw_text
+fn spacing(size: i32) -> f32 {
+    (size / 10).max(1) as f32
+}
+
+pub fn draw_text(d: &mut impl RaylibDraw, text: &str, x: i32, y: i32, size: i32, color: Color) {
+    FONT.with(|cell| match cell.borrow().as_ref() {
+        Some(font) => d.draw_text_ex(font, text, Vector2::new(x as f32, y as f32), size as f32, spacing(size), color),
+        None => d.draw_text(text, x, y, size, color),
+    })
+}
+
+pub fn measure_text(text: &str, size: i32) -> i32 {
+    FONT.with(|cell| match cell.borrow().as_ref() {
+        Some(font) => measure_text_ex(font, text, size as f32, spacing(size)).x as i32,
+        None => raylib::prelude::measure_text(text, size),
+    })
+}
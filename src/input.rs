@@ -0,0 +1,552 @@
+use crate::config::{key_name, Config, GamepadConfig, Keybinds};
+use crate::Direction;
+use raylib::prelude::*;
+use std::time::{Duration, Instant};
+
+// what the keybinds do. the game asks whether an action was used rather than checking for keys,
+// so every action can be bound to any keys in the config file or on the settings screen
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Undo,
+    Redo,
+    Settings,
+    Continue,
+    CopySeed,
+    CopyMoves,
+    Daily,
+    Hint,
+    AutoSolve,
+    WatchReplay,
+    Leaderboard,
+    Statistics,
+    ExportStatistics,
+    NextTrack,
+    MuteMusic,
+    Mute,
+    AudioSettings,
+    NextTheme,
+    Thumbnail,
+    GoalOverlay,
+    Fullscreen,
+    Screenshot,
+    Scramble,
+    Retry,
+    Share,
+    SwitchBlank,
+}
+
+impl Action {
+    // in the order the settings screen lists them
+    pub const ALL: [Action; 30] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Undo,
+        Action::Redo,
+        Action::Settings,
+        Action::Continue,
+        Action::CopySeed,
+        Action::CopyMoves,
+        Action::Daily,
+        Action::Hint,
+        Action::AutoSolve,
+        Action::WatchReplay,
+        Action::Leaderboard,
+        Action::Statistics,
+        Action::ExportStatistics,
+        Action::NextTrack,
+        Action::MuteMusic,
+        Action::Mute,
+        Action::AudioSettings,
+        Action::NextTheme,
+        Action::Thumbnail,
+        Action::GoalOverlay,
+        Action::Fullscreen,
+        Action::Screenshot,
+        Action::Scramble,
+        Action::Retry,
+        Action::Share,
+        Action::SwitchBlank,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Left => "Move left",
+            Action::Right => "Move right",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Settings => "Settings",
+            Action::Continue => "Continue",
+            Action::CopySeed => "Copy seed",
+            Action::CopyMoves => "Copy moves",
+            Action::Daily => "Daily puzzle",
+            Action::Hint => "Hint",
+            Action::AutoSolve => "Auto-solve",
+            Action::WatchReplay => "Watch replay",
+            Action::Leaderboard => "Leaderboard",
+            Action::Statistics => "Statistics",
+            Action::ExportStatistics => "Export statistics",
+            Action::NextTrack => "Next track",
+            Action::MuteMusic => "Mute music",
+            Action::Mute => "Mute",
+            Action::AudioSettings => "Audio settings",
+            Action::NextTheme => "Next theme",
+            Action::Thumbnail => "Thumbnail",
+            Action::GoalOverlay => "Goal overlay",
+            Action::Fullscreen => "Fullscreen",
+            Action::Screenshot => "Screenshot",
+            Action::Scramble => "New scramble",
+            Action::Retry => "Retry scramble",
+            Action::Share => "Copy solve summary",
+            Action::SwitchBlank => "Switch blank",
+        }
+    }
+}
+
+// the movement keybinds, by the way they move the empty cell
+pub const MOVES: [(Action, Direction); 4] = [
+    (Action::Up, Direction::Up),
+    (Action::Down, Direction::Down),
+    (Action::Left, Direction::Left),
+    (Action::Right, Direction::Right),
+];
+
+// the extra movement keys of the wasd, the vim and the numpad scheme
+const WASD_KEYS: [(KeyboardKey, Action); 4] = [
+    (KeyboardKey::KEY_W, Action::Up),
+    (KeyboardKey::KEY_A, Action::Left),
+    (KeyboardKey::KEY_S, Action::Down),
+    (KeyboardKey::KEY_D, Action::Right),
+];
+const VIM_KEYS: [(KeyboardKey, Action); 4] = [
+    (KeyboardKey::KEY_K, Action::Up),
+    (KeyboardKey::KEY_H, Action::Left),
+    (KeyboardKey::KEY_J, Action::Down),
+    (KeyboardKey::KEY_L, Action::Right),
+];
+const NUMPAD_KEYS: [(KeyboardKey, Action); 4] = [
+    (KeyboardKey::KEY_KP_8, Action::Up),
+    (KeyboardKey::KEY_KP_4, Action::Left),
+    (KeyboardKey::KEY_KP_2, Action::Down),
+    (KeyboardKey::KEY_KP_6, Action::Right),
+];
+
+impl Keybinds {
+    pub fn keys(&self, action: Action) -> &[KeyboardKey] {
+        match action {
+            Action::Up => &self.up,
+            Action::Down => &self.down,
+            Action::Left => &self.left,
+            Action::Right => &self.right,
+            Action::Undo => &self.undo,
+            Action::Redo => &self.redo,
+            Action::Settings => &self.settings,
+            Action::Continue => &self.next_level,
+            Action::CopySeed => &self.copy_seed,
+            Action::CopyMoves => &self.copy_moves,
+            Action::Daily => &self.daily,
+            Action::Hint => &self.hint,
+            Action::AutoSolve => &self.auto_solve,
+            Action::WatchReplay => &self.watch_replay,
+            Action::Leaderboard => &self.leaderboard,
+            Action::Statistics => &self.statistics,
+            Action::ExportStatistics => &self.export_statistics,
+            Action::NextTrack => &self.next_track,
+            Action::MuteMusic => &self.mute_music,
+            Action::Mute => &self.mute,
+            Action::AudioSettings => &self.audio_settings,
+            Action::NextTheme => &self.next_theme,
+            Action::Thumbnail => &self.thumbnail,
+            Action::GoalOverlay => &self.goal_overlay,
+            Action::Fullscreen => &self.fullscreen,
+            Action::Screenshot => &self.screenshot,
+            Action::Scramble => &self.scramble,
+            Action::Retry => &self.retry,
+            Action::Share => &self.share,
+            Action::SwitchBlank => &self.switch_blank,
+        }
+    }
+
+    pub fn keys_mut(&mut self, action: Action) -> &mut Vec<KeyboardKey> {
+        match action {
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+            Action::Undo => &mut self.undo,
+            Action::Redo => &mut self.redo,
+            Action::Settings => &mut self.settings,
+            Action::Continue => &mut self.next_level,
+            Action::CopySeed => &mut self.copy_seed,
+            Action::CopyMoves => &mut self.copy_moves,
+            Action::Daily => &mut self.daily,
+            Action::Hint => &mut self.hint,
+            Action::AutoSolve => &mut self.auto_solve,
+            Action::WatchReplay => &mut self.watch_replay,
+            Action::Leaderboard => &mut self.leaderboard,
+            Action::Statistics => &mut self.statistics,
+            Action::ExportStatistics => &mut self.export_statistics,
+            Action::NextTrack => &mut self.next_track,
+            Action::MuteMusic => &mut self.mute_music,
+            Action::Mute => &mut self.mute,
+            Action::AudioSettings => &mut self.audio_settings,
+            Action::NextTheme => &mut self.next_theme,
+            Action::Thumbnail => &mut self.thumbnail,
+            Action::GoalOverlay => &mut self.goal_overlay,
+            Action::Fullscreen => &mut self.fullscreen,
+            Action::Screenshot => &mut self.screenshot,
+            Action::Scramble => &mut self.scramble,
+            Action::Retry => &mut self.retry,
+            Action::Share => &mut self.share,
+            Action::SwitchBlank => &mut self.switch_blank,
+        }
+    }
+
+    // binds the action to the key instead of the keys it had, or as one more of them
+    pub fn bind(&mut self, action: Action, key: KeyboardKey, add: bool) {
+        let keys = self.keys_mut(action);
+
+        if !add {
+            keys.clear();
+        }
+
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    // the movement keys of the schemes that are turned on
+    fn scheme_keys(&self) -> Vec<(KeyboardKey, Action)> {
+        let mut keys = Vec::new();
+
+        if self.wasd {
+            keys.extend(WASD_KEYS);
+        }
+
+        if self.vim {
+            keys.extend(VIM_KEYS);
+        }
+
+        if self.numpad {
+            keys.extend(NUMPAD_KEYS);
+        }
+
+        keys
+    }
+
+    pub fn is_pressed(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.any_key(action, |key| rl.is_key_pressed(key))
+    }
+
+    pub fn is_down(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.any_key(action, |key| rl.is_key_down(key))
+    }
+
+    // whether the key is one of the action's
+    pub fn binds(&self, action: Action, key: KeyboardKey) -> bool {
+        self.any_key(action, |bound| bound == key)
+    }
+
+    // whether the check holds for any key of the action. the keys of a movement scheme only move
+    // while it is turned on, even if something else is bound to them
+    fn any_key(&self, action: Action, check: impl Fn(KeyboardKey) -> bool) -> bool {
+        let scheme_keys = self.scheme_keys();
+        let in_scheme = |key: &KeyboardKey| scheme_keys.iter().any(|(scheme_key, _)| scheme_key == key);
+
+        let bound = self.keys(action).iter().any(|key| !in_scheme(key) && check(*key));
+        let moved = scheme_keys.iter().any(|(key, moves)| *moves == action && check(*key));
+        bound || moved
+    }
+
+    // the keybinds while the w a s d keys are the second player's, in a versus race. their scheme is
+    // turned on so nothing else of the first player's is on them
+    pub fn with_second_player(&self, versus: bool) -> Keybinds {
+        Keybinds {
+            wasd: self.wasd || versus,
+            ..self.clone()
+        }
+    }
+
+    // the key the hud and the screens ask for, the first the action is bound to
+    pub fn key_name(&self, action: Action) -> &str {
+        self.keys(action).first().map_or("NULL", |key| key_name::name_of(*key))
+    }
+
+    // every key of the action, for the settings screen
+    pub fn key_names(&self, action: Action) -> String {
+        let names: Vec<&str> = self.keys(action).iter().map(|key| key_name::name_of(*key)).collect();
+        names.join(", ")
+    }
+}
+
+// the number keys that start a board of their size, on the menu and once a board is solved
+const SIZE_KEYS: [(KeyboardKey, u8); 7] = [
+    (KeyboardKey::KEY_THREE, 3),
+    (KeyboardKey::KEY_FOUR, 4),
+    (KeyboardKey::KEY_FIVE, 5),
+    (KeyboardKey::KEY_SIX, 6),
+    (KeyboardKey::KEY_SEVEN, 7),
+    (KeyboardKey::KEY_EIGHT, 8),
+    (KeyboardKey::KEY_NINE, 9),
+];
+
+// the second player's keys in a versus race, for the hud, e.g. "W A S D"
+pub fn second_player_key_names() -> String {
+    let names: Vec<&str> = WASD_KEYS.iter().map(|(key, _)| key_name::name_of(*key)).collect();
+    names.join(" ")
+}
+
+pub fn pressed_board_size(rl: &RaylibHandle) -> Option<u8> {
+    SIZE_KEYS.iter().find(|(key, _)| rl.is_key_pressed(*key)).map(|(_, size)| *size)
+}
+
+// what a controller can do, the things the keyboard has the arrows, enter and escape for
+#[derive(Clone, Copy, PartialEq)]
+pub enum Button {
+    Move(Direction),
+    Confirm,
+    Pause,
+}
+
+// the buttons pressed on the first controller this frame. the left stick moves like the d-pad,
+// once each time it is pushed out of the deadzone
+pub struct Gamepad {
+    pressed: Vec<Button>,
+    stick: Option<Direction>,
+}
+
+impl Gamepad {
+    pub fn new() -> Gamepad {
+        Gamepad {
+            pressed: Vec::new(),
+            stick: None,
+        }
+    }
+
+    // reads the controller, once at the start of every frame
+    pub fn update(&mut self, rl: &RaylibHandle, config: &GamepadConfig) {
+        self.pressed.clear();
+
+        if !rl.is_gamepad_available(0) {
+            self.stick = None;
+            return;
+        }
+
+        let buttons = [
+            (config.up, Button::Move(Direction::Up)),
+            (config.down, Button::Move(Direction::Down)),
+            (config.left, Button::Move(Direction::Left)),
+            (config.right, Button::Move(Direction::Right)),
+            (config.confirm, Button::Confirm),
+            (config.pause, Button::Pause),
+        ];
+
+        for (gamepad_button, button) in buttons {
+            if rl.is_gamepad_button_pressed(0, gamepad_button) {
+                self.pressed.push(button);
+            }
+        }
+
+        // the axis the stick is pushed furthest along decides the direction, down is positive
+        let x = rl.get_gamepad_axis_movement(0, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
+        let y = rl.get_gamepad_axis_movement(0, GamepadAxis::GAMEPAD_AXIS_LEFT_Y);
+
+        let stick = match (x, y) {
+            (x, y) if x.abs().max(y.abs()) < config.deadzone => None,
+            (x, y) if x.abs() > y.abs() && x < 0.0 => Some(Direction::Left),
+            (x, y) if x.abs() > y.abs() => Some(Direction::Right),
+            (_, y) if y < 0.0 => Some(Direction::Up),
+            _ => Some(Direction::Down),
+        };
+
+        if let Some(direction) = stick.filter(|direction| self.stick != Some(*direction)) {
+            self.pressed.push(Button::Move(direction));
+        }
+
+        self.stick = stick;
+    }
+
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.pressed.contains(&button)
+    }
+}
+
+// how far a finger or the mouse has to move between pressing and letting go to swipe, rather than
+// tapping where it was pressed
+const SWIPE_DISTANCE: f32 = 30.0;
+
+#[derive(Clone, Copy)]
+pub enum TouchGesture {
+    Tap(Vector2),
+    // where the swipe started, and the direction it went in the most
+    Swipe(Vector2, Direction),
+}
+
+// taps and swipes, by touch or with the mouse. raylib reports the first finger on a touchscreen as
+// the mouse, so both work the same. a gesture is only known once it is let go of
+pub struct Touch {
+    start: Option<Vector2>,
+    gesture: Option<TouchGesture>,
+}
+
+impl Touch {
+    pub fn new() -> Touch {
+        Touch {
+            start: None,
+            gesture: None,
+        }
+    }
+
+    // once at the start of every frame, like the gamepad
+    pub fn update(&mut self, rl: &RaylibHandle) {
+        self.gesture = None;
+
+        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            self.start = Some(rl.get_mouse_position());
+        }
+
+        if !rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) {
+            return;
+        }
+
+        let start = match self.start.take() {
+            Some(start) => start,
+            None => return,
+        };
+
+        let drag = rl.get_mouse_position() - start;
+
+        self.gesture = if drag.length() < SWIPE_DISTANCE {
+            Some(TouchGesture::Tap(start))
+        } else if drag.x.abs() > drag.y.abs() {
+            Some(TouchGesture::Swipe(start, if drag.x < 0.0 { Direction::Left } else { Direction::Right }))
+        } else {
+            Some(TouchGesture::Swipe(start, if drag.y < 0.0 { Direction::Up } else { Direction::Down }))
+        };
+    }
+
+    pub fn gesture(&self) -> Option<TouchGesture> {
+        self.gesture
+    }
+}
+
+// what was pressed, held or swiped this frame, read once at the start of every frame
+pub struct Input {
+    pub gamepad: Gamepad,
+    pub touch: Touch,
+    // every key pressed since the last frame, in the order they were pressed
+    keys: Vec<KeyboardKey>,
+    // the moves asked for since the last frame, for the game to make in order
+    moves: Vec<Direction>,
+    // the second player's moves in a split-screen race
+    second_moves: Vec<Direction>,
+    // the movement key held down, and when it moves again
+    held: Option<(Direction, Instant)>,
+}
+
+impl Input {
+    pub fn new() -> Input {
+        Input {
+            gamepad: Gamepad::new(),
+            touch: Touch::new(),
+            keys: Vec::new(),
+            moves: Vec::new(),
+            second_moves: Vec::new(),
+            held: None,
+        }
+    }
+
+    pub fn update(&mut self, rl: &mut RaylibHandle, config: &Config) {
+        self.gamepad.update(rl, &config.gamepad);
+        self.touch.update(rl);
+
+        // raylib queues up the presses of a frame, where is_key_pressed only tells whether a key was
+        // pressed at all. going through the queue keeps quick presses like up, up, left at a low fps
+        // from being dropped or made in the wrong order
+        self.keys.clear();
+
+        while let Some(key) = rl.get_key_pressed() {
+            self.keys.push(key);
+        }
+
+        let keybinds = &config.keybinds.with_second_player(config.versus);
+        let second_player = |key: &KeyboardKey| config.versus && WASD_KEYS.iter().any(|(wasd, _)| wasd == key);
+        let (second_keys, keys): (Vec<_>, Vec<_>) = self.keys.iter().copied().partition(second_player);
+
+        self.second_moves = second_keys
+            .iter()
+            .filter_map(|key| WASD_KEYS.iter().find(|(wasd, _)| wasd == key))
+            .filter_map(|(_, action)| MOVES.iter().find(|(moves, _)| moves == action))
+            .map(|(_, direction)| *direction)
+            .collect();
+
+        self.moves = keys
+            .iter()
+            .filter_map(|key| MOVES.iter().find(|(action, _)| keybinds.binds(*action, *key)))
+            .map(|(_, direction)| *direction)
+            .collect();
+
+        for (_, direction) in MOVES {
+            if self.gamepad.is_pressed(Button::Move(direction)) {
+                self.moves.push(direction);
+            }
+        }
+
+        if let Some(direction) = self.update_repeat(rl, config) {
+            self.moves.push(direction);
+        }
+    }
+
+    // holding a movement key moves again after the delay, then at the interval. pressing it moves by
+    // itself, that starts the delay over. keys don't repeat in a split-screen race, where only one
+    // of the players could hold theirs down
+    fn update_repeat(&mut self, rl: &RaylibHandle, config: &Config) -> Option<Direction> {
+        let held = MOVES.iter().find(|(action, _)| config.keybinds.is_down(rl, *action));
+
+        let (action, direction) = match held {
+            Some(held) if config.key_repeat && !config.versus => *held,
+            _ => {
+                self.held = None;
+                return None;
+            }
+        };
+
+        let now = Instant::now();
+
+        match self.held {
+            Some((held, next)) if held == direction && !config.keybinds.is_pressed(rl, action) => {
+                if now < next {
+                    return None;
+                }
+
+                self.held = Some((direction, now + Duration::from_millis(config.key_repeat_interval_ms)));
+                Some(direction)
+            }
+            _ => {
+                self.held = Some((direction, now + Duration::from_millis(config.key_repeat_delay_ms)));
+                None
+            }
+        }
+    }
+
+    pub fn pressed_keys(&self) -> &[KeyboardKey] {
+        &self.keys
+    }
+
+    // the moves to make this frame, by the keys, the controller and holding keys down. moves not
+    // taken by the end of the frame are dropped, e.g. while the board is paused
+    pub fn take_moves(&mut self) -> Vec<Direction> {
+        std::mem::take(&mut self.moves)
+    }
+
+    pub fn take_second_moves(&mut self) -> Vec<Direction> {
+        std::mem::take(&mut self.second_moves)
+    }
+}
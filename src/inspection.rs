@@ -0,0 +1,49 @@
+use crate::events::{Event, EventBus, Subscriber};
+use crate::Game;
+use std::time::{Duration, Instant};
+
+// the wca style inspection and the stackmat start, both are set up again for every timed solve
+pub struct Inspection {
+    // when the inspection of the board being played ends, while it is being inspected
+    pub ends: Option<Instant>,
+    pub duration: Duration,
+    // set while a timed solve waits for the stackmat start, with when the key was pressed while it is held
+    pub stackmat_armed: bool,
+    pub stackmat_held: Option<Instant>,
+    pub stackmat_hold_time: Duration,
+    inspect: bool,
+    stackmat: bool,
+}
+
+impl Inspection {
+    pub fn new(inspect: bool, duration: Duration, stackmat: bool, stackmat_hold_time: Duration) -> Inspection {
+        Inspection {
+            ends: None,
+            duration,
+            stackmat_armed: false,
+            stackmat_held: None,
+            stackmat_hold_time,
+            inspect,
+            stackmat,
+        }
+    }
+
+    // whether the board has to stay put until the timer is started
+    pub fn is_waiting(&self) -> bool {
+        self.ends.is_some() || self.stackmat_armed
+    }
+}
+
+impl Subscriber for Inspection {
+    // only timed solves are inspected
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        if event != Event::ScrambleStarted {
+            return;
+        }
+
+        let timed = game.counts_for_stats();
+        self.ends = (self.inspect && timed).then(|| Instant::now() + self.duration);
+        self.stackmat_armed = self.stackmat && timed;
+        self.stackmat_held = None;
+    }
+}
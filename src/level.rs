@@ -0,0 +1,38 @@
+use crate::Board;
+use std::fs;
+use std::path::Path;
+
+// a level file draws the board row by row, '.' for a cell and '#' for a wall, e.g.
+//
+//   ....
+//   .##.
+//   ....
+//
+// the tiles are numbered around the walls and the empty cell starts in the last cell, so that
+// one can't be a wall
+pub fn load(path: &Path) -> Result<Board, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    parse(&text).map_err(|error| format!("invalid level {}: {}", path.display(), error))
+}
+
+fn parse(text: &str) -> Result<Board, String> {
+    let rows: Vec<&str> = text.lines().map(str::trim).filter(|row| !row.is_empty()).collect();
+    let width = rows.first().map_or(0, |row| row.chars().count());
+
+    if rows.iter().any(|row| row.chars().count() != width) {
+        return Err("every row has to have the same number of cells".to_owned());
+    }
+
+    let mut walls = Vec::new();
+
+    for (index, cell) in rows.iter().flat_map(|row| row.chars()).enumerate() {
+        match cell {
+            '.' => {}
+            '#' => walls.push(index),
+            _ => return Err(format!("invalid cell: {}", cell)),
+        }
+    }
+
+    let size = |side: usize| u8::try_from(side).map_err(|_| "the board is too big".to_owned());
+    Board::with_walls(size(width)?, size(rows.len())?, walls)
+}
@@ -0,0 +1,1026 @@
+// the puzzle itself, with nothing to do with drawing it or playing it in a window, so it can be
+// used headless as well
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::AtomicBool;
+
+pub mod notation;
+pub mod solver;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Board {
+    pub cells: Vec<i32>,
+    pub width: u8,
+    pub height: u8,
+    pub solved: bool,
+    // boards saved before there were other goals are solved row by row
+    #[serde(default)]
+    pub goal: Goal,
+    // the two blanks variant, where the second highest value is empty as well. either empty cell
+    // can be moved, the moves go to the second one while second_blank is set
+    #[serde(default)]
+    pub two_blanks: bool,
+    #[serde(default)]
+    pub second_blank: bool,
+    // the toroidal variant, where moves off one edge of the board come back in on the other
+    #[serde(default)]
+    pub wrap: bool,
+    // the letter of every tile by its value for the letters variant, tiles past the last letter keep
+    // their numbers
+    #[serde(default)]
+    pub letters: Vec<char>,
+    // the cells of a level that are walls, by index. a wall keeps the value of its cell in the goal
+    // and tiles can never move onto it, so it is always in place
+    #[serde(default)]
+    pub walls: Vec<usize>,
+    // the gravity variant, where the tiles above the empty cell fall into it after every move
+    #[serde(default)]
+    pub gravity: bool,
+    // the position to reach in the target mode, in place of the goal's solved position
+    #[serde(default)]
+    pub target: Option<Vec<i32>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl From<i32> for Direction {
+    fn from(direction: i32) -> Self {
+        match direction {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            3 => Direction::Right,
+            _ => panic!("Invalid direction"),
+        }
+    }
+}
+
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Random,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Random,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Random => "Random",
+        }
+    }
+
+    // the number of random moves used to scramble the board, or None for a fully random board.
+    // a scramble of n moves can always be undone in n moves, so this is also an upper bound
+    // on the optimal solution. easy boards are checked with the solver as well, see EASY_MAX_MOVES
+    pub fn scramble_depth(&self) -> Option<std::ops::Range<usize>> {
+        match self {
+            Difficulty::Easy => Some(6..15),
+            Difficulty::Medium => Some(25..50),
+            Difficulty::Hard => Some(80..150),
+            Difficulty::Random => None,
+        }
+    }
+}
+
+// the order the tiles are in once the board is solved
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Goal {
+    #[default]
+    RowMajor,
+    ColumnMajor,
+    // clockwise from the top left corner, ending with the empty cell
+    Spiral,
+    // the tiles counting down, the empty cell is still last
+    Reverse,
+    // the empty cell in the top left corner, the tiles after it row by row
+    BlankFirst,
+}
+
+impl Goal {
+    pub const ALL: [Goal; 5] = [Goal::RowMajor, Goal::ColumnMajor, Goal::Spiral, Goal::Reverse, Goal::BlankFirst];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Goal::RowMajor => "Row by row",
+            Goal::ColumnMajor => "Column by column",
+            Goal::Spiral => "Spiral",
+            Goal::Reverse => "Reverse",
+            Goal::BlankFirst => "Blank first",
+        }
+    }
+
+    // the value of every cell of a solved board of the size, width * height being the empty cell
+    pub fn cells(&self, width: u8, height: u8) -> Vec<i32> {
+        let (width, height) = (width as usize, height as usize);
+        let count = width * height;
+
+        match self {
+            Goal::RowMajor => (1..count as i32 + 1).collect(),
+            Goal::ColumnMajor => (0..count).map(|i| ((i % width) * height + i / width) as i32 + 1).collect(),
+            Goal::Spiral => {
+                let mut cells = vec![0; count];
+                let (mut row, mut column) = (0, 0);
+                // right, down, left, up. the walk turns whenever it would leave the board or reach a
+                // cell it was on already
+                let steps: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+                let mut step = 0;
+
+                for value in 1..count as i32 + 1 {
+                    cells[row * width + column] = value;
+
+                    for _ in 0..steps.len() {
+                        let next_row = row as isize + steps[step].0;
+                        let next_column = column as isize + steps[step].1;
+                        let inside =
+                            (0..height as isize).contains(&next_row) && (0..width as isize).contains(&next_column);
+
+                        if inside && cells[next_row as usize * width + next_column as usize] == 0 {
+                            (row, column) = (next_row as usize, next_column as usize);
+                            break;
+                        }
+
+                        step = (step + 1) % steps.len();
+                    }
+                }
+
+                cells
+            }
+            Goal::Reverse => (1..count as i32).rev().chain([count as i32]).collect(),
+            Goal::BlankFirst => [count as i32].into_iter().chain(1..count as i32).collect(),
+        }
+    }
+}
+
+pub const MIN_BOARD_SIZE: u8 = 3;
+pub const MAX_BOARD_SIZE: u8 = 10;
+// the compact position format writes each tile as a single character, in this order
+pub const COMPACT_CHARS: &str = "123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+// how many moves boards that can't be shuffled are scrambled with, whatever the difficulty
+const WALK_SCRAMBLE_DEPTH: std::ops::Range<usize> = 300..500;
+// easy scrambles are solvable in fewer moves than this. the falls of gravity boards can make a walk
+// longer than its depth, so the solver checks every easy board and ones over it are scrambled again
+pub const EASY_MAX_MOVES: usize = 15;
+// a board of fewer than EASY_MAX_MOVES moves is solved long before this
+const EASY_CHECK_MAX_NODES: u64 = 1_000_000;
+// gives up on finding an easy board after this many tries and keeps the last one, in case falls
+// always make the walk too long
+const EASY_ATTEMPTS: usize = 100;
+
+// parses a board size given as either "<size>" or "<width>x<height>"
+pub fn parse_board_size(value: &str) -> Result<(u8, u8), String> {
+    let parse_side = |side: &str| {
+        side.trim()
+            .parse::<u8>()
+            .map_err(|_| format!("invalid board size: {}", value))
+    };
+
+    let (width, height) = match value.split_once('x') {
+        Some((width, height)) => (parse_side(width)?, parse_side(height)?),
+        None => {
+            let size = parse_side(value)?;
+            (size, size)
+        }
+    };
+
+    for side in [width, height] {
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&side) {
+            return Err(format!(
+                "board sides must be between {} and {}",
+                MIN_BOARD_SIZE, MAX_BOARD_SIZE
+            ));
+        }
+    }
+
+    Ok((width, height))
+}
+
+impl Board {
+    pub fn new(cells: Vec<i32>, width: u8, height: u8) -> Board {
+        let mut board = Board::unchecked(cells, width, height);
+        board.check_solved();
+        board
+    }
+
+    // the board before it is known whether the cells are valid, check_solved can't be trusted with
+    // cells that aren't
+    fn unchecked(cells: Vec<i32>, width: u8, height: u8) -> Board {
+        Board {
+            cells,
+            width,
+            height,
+            solved: false,
+            goal: Goal::RowMajor,
+            two_blanks: false,
+            second_blank: false,
+            wrap: false,
+            letters: Vec::new(),
+            walls: Vec::new(),
+            gravity: false,
+            target: None,
+        }
+    }
+
+    // the same seed, difficulty and board size always give the same scramble.
+    // ChaCha8 is used rather than StdRng since its output is guaranteed to never change,
+    // so seeds can be shared between players. returns the moves of the empty cell that lead from the
+    // solved board to the scramble, none for boards with two empty cells since both of them moved.
+    // fails for boards whose walls leave the empty cell no way out
+    pub fn scramble(&mut self, difficulty: Difficulty, seed: u64) -> Result<Vec<Direction>, String> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        // a shuffle could put tiles where the walls keep them from ever getting out, or where gravity
+        // wouldn't let them stay, so those boards are scrambled with a long random walk instead
+        let walk_only = !self.walls.is_empty() || self.gravity;
+        let depth = difficulty.scramble_depth().or(walk_only.then_some(WALK_SCRAMBLE_DEPTH));
+
+        match depth {
+            Some(depth) if difficulty == Difficulty::Easy => {
+                let mut moves = self.scramble_moves(depth.clone(), &mut rng)?;
+
+                for _ in 1..EASY_ATTEMPTS {
+                    if self.is_easy(&moves) {
+                        break;
+                    }
+
+                    moves = self.scramble_moves(depth.clone(), &mut rng)?;
+                }
+
+                Ok(moves)
+            }
+            Some(depth) => self.scramble_moves(depth, &mut rng),
+            None => {
+                self.scramble_shuffle(&mut rng);
+
+                // a shuffle has no moves of its own, so the board is solved and the solution is
+                // taken back instead
+                let solution = solver::solve_quickly(self).unwrap_or_default();
+                Ok(solution.iter().rev().map(|direction| direction.opposite()).collect())
+            }
+        }
+    }
+
+    // the target mode starts from the usual solved board, a scramble of it is the position to
+    // reach instead
+    pub fn scramble_target(&mut self, difficulty: Difficulty, seed: u64) {
+        // a board without walls can always be scrambled
+        let mut target = Board::solved(self.width, self.height);
+        let _ = target.scramble(difficulty, seed);
+
+        self.cells = self.goal.cells(self.width, self.height);
+        self.target = Some(target.cells);
+        self.check_solved();
+    }
+
+    // a board solved but for its last rows, which are shuffled, for practicing the end of a solve.
+    // returns the moves that lead to it like scramble does
+    pub fn scramble_endgame(&mut self, rows: u8, seed: u64) -> Vec<Direction> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let first = (self.height - rows.min(self.height)) as usize * self.width as usize;
+        self.cells = self.goal_cells();
+
+        // the same as scramble_shuffle, but only the cells from first on
+        loop {
+            self.cells[first..].shuffle(&mut rng);
+
+            if !self.is_solvable() {
+                let empty_value = self.get_empty_value();
+                let mut tiles = (first..self.cells.len()).filter(|i| self.cells[*i] != empty_value);
+                let first_tile = tiles.next().unwrap();
+                let second_tile = tiles.next().unwrap();
+                self.cells.swap(first_tile, second_tile);
+            }
+
+            self.check_solved();
+
+            if !self.solved {
+                break;
+            }
+        }
+
+        let solution = solver::solve_quickly(self).unwrap_or_default();
+        solution.iter().rev().map(|direction| direction.opposite()).collect()
+    }
+
+    // whether the solver finds a solution of fewer than EASY_MAX_MOVES moves. boards the solver can't
+    // solve, e.g. with two blanks or gravity, go by the moves of the scramble instead
+    fn is_easy(&self, moves: &[Direction]) -> bool {
+        let heuristic = solver::SolverHeuristic::WalkingDistance;
+        let solution = solver::solve(self, heuristic, EASY_CHECK_MAX_NODES, &AtomicBool::new(false));
+        solution.map_or(moves.len(), |solution| solution.len()) < EASY_MAX_MOVES
+    }
+
+    fn scramble_moves(
+        &mut self,
+        depth: std::ops::Range<usize>,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<Direction>, String> {
+        loop {
+            // perform a random walk from the solved board that never steps back onto
+            // the cell it just came from, so every move counts towards the depth
+            self.cells = self.goal_cells();
+            let move_count = rng.gen_range(depth.clone());
+            let mut last_direction: Option<Direction> = None;
+            let mut moves = Vec::with_capacity(move_count);
+
+            while moves.len() < move_count {
+                if self.two_blanks && rng.gen_bool(0.5) {
+                    self.second_blank = !self.second_blank;
+                }
+
+                let direction = Direction::from(rng.gen_range(0..4));
+                let is_backtrack = last_direction.map(|last| last.opposite()) == Some(direction);
+                // walls can leave stepping back as the only way out of a dead end
+                let empty_index = self.get_empty_index();
+                let ways_out = (0..4).filter(|i| self.get_neighbor_index(empty_index, Direction::from(*i)).is_some());
+                let ways_out = ways_out.count();
+
+                // the walk would never get anywhere
+                if ways_out == 0 {
+                    self.cells = self.goal_cells();
+                    return Err("the walls leave the empty cell no way out".to_owned());
+                }
+
+                if (is_backtrack && ways_out > 1) || !self.move_empty(direction) {
+                    continue;
+                }
+
+                last_direction = Some(direction);
+                moves.push(direction);
+                moves.extend(self.settle());
+            }
+
+            // if we accidentally create a solved board, we try again
+            if !self.solved && self.two_blanks {
+                self.second_blank = false;
+                return Ok(Vec::new());
+            }
+
+            if !self.solved {
+                return Ok(moves);
+            }
+        }
+    }
+
+    fn scramble_shuffle(&mut self, rng: &mut impl Rng) {
+        self.cells = self.goal_cells();
+
+        // shuffle uniformly, then fix the parity if we landed on an unsolvable board
+        // by swapping two tiles. if we accidentally create a solved board, we try again
+        loop {
+            self.cells.shuffle(rng);
+
+            if !self.is_solvable() {
+                let empty_value = self.get_empty_value();
+                let mut tiles = (0..self.cells.len()).filter(|i| self.cells[*i] != empty_value);
+                let first = tiles.next().unwrap();
+                let second = tiles.next().unwrap();
+                self.cells.swap(first, second);
+            }
+
+            self.check_solved();
+
+            if !self.solved {
+                break;
+            }
+        }
+    }
+
+    // a board is solvable when the parity of the permutation that puts every tile where it belongs
+    // in the goal (counting the empty cell as a tile) matches the parity of the empty cell's distance
+    // to its goal position, since every move changes both by one
+    pub fn is_solvable(&self) -> bool {
+        // the empty cells can take each other's place, which makes up for a wrong parity
+        if self.two_blanks {
+            return true;
+        }
+
+        // a move around an odd side takes the empty cell an even number of cells away while
+        // changing the permutation's parity, so any position can be reached then
+        if self.wrap && (self.width % 2 == 1 || self.height % 2 == 1) {
+            return true;
+        }
+
+        // two tiles with the same letter can take each other's place without it showing
+        self.is_permutation_solvable() || self.duplicate_letter_tiles().is_some()
+    }
+
+    // whether every tile can be put in exactly its own goal position
+    fn is_permutation_solvable(&self) -> bool {
+        let goal_indices = self.goal_indices();
+        let indices: Vec<usize> = self.cells.iter().map(|cell| goal_indices[*cell as usize - 1]).collect();
+        let mut inversions = 0;
+
+        for i in 0..indices.len() {
+            for j in i + 1..indices.len() {
+                if indices[i] > indices[j] {
+                    inversions += 1;
+                }
+            }
+        }
+
+        let width = self.width as usize;
+        let empty_index = self.get_empty_index();
+        let goal_index = goal_indices[self.get_empty_value() as usize - 1];
+        let empty_distance = (goal_index / width).abs_diff(empty_index / width)
+            + (goal_index % width).abs_diff(empty_index % width);
+
+        inversions % 2 == empty_distance % 2
+    }
+
+    // two tiles with the same letter, if there are any
+    fn duplicate_letter_tiles(&self) -> Option<(i32, i32)> {
+        let tiles: Vec<i32> = (1..=self.get_cell_count() as i32).filter(|tile| !self.is_empty_value(*tile)).collect();
+
+        tiles.iter().enumerate().find_map(|(i, tile)| {
+            let other = tiles[i + 1..].iter().find(|other| self.is_same_tile(*tile, **other))?;
+            Some((*tile, *other))
+        })
+    }
+
+    // a board that looks the same but can be solved tile for tile, so the solver can be used on
+    // it. a board of letters that couldn't be has two tiles with the same letter swapped
+    pub fn solvable_twin(&self) -> Board {
+        let mut twin = self.clone();
+
+        if let (false, Some((tile, other))) = (self.is_permutation_solvable(), self.duplicate_letter_tiles()) {
+            let first = twin.cells.iter().position(|cell| *cell == tile).unwrap();
+            let second = twin.cells.iter().position(|cell| *cell == other).unwrap();
+            twin.cells.swap(first, second);
+        }
+
+        twin
+    }
+
+    // whether the board plays by the rules of one of the variants, two blanks, wraparound, letters,
+    // walls or gravity
+    pub fn is_variant(&self) -> bool {
+        self.two_blanks || self.wrap || !self.letters.is_empty() || !self.walls.is_empty() || self.gravity
+    }
+
+    // what the tile shows, its letter or its number. the walls of a level are skipped, so the tiles
+    // are still numbered from 1 on without gaps
+    pub fn tile_label(&self, tile: i32) -> String {
+        let goal_cells = self.goal_cells();
+        let walls_before = (self.walls.iter()).filter(|wall| goal_cells[**wall] < tile).count() as i32;
+
+        match self.letters.get(tile as usize - 1) {
+            Some(letter) => letter.to_string(),
+            None => (tile - walls_before).to_string(),
+        }
+    }
+
+    // a solved board with walls in the given cells, for levels
+    pub fn with_walls(width: u8, height: u8, walls: Vec<usize>) -> Result<Board, String> {
+        let mut board = Board::solved(width, height);
+
+        if !board.is_valid() {
+            return Err(format!("board sides have to be from {} to {}", MIN_BOARD_SIZE, MAX_BOARD_SIZE));
+        }
+
+        if walls.iter().any(|wall| *wall >= board.get_empty_index()) {
+            return Err("the last cell is where the empty cell starts, it can't be a wall".to_owned());
+        }
+
+        if walls.len() + 3 > board.get_cell_count() {
+            return Err("a level needs at least two tiles besides the walls".to_owned());
+        }
+
+        board.walls = walls;
+
+        // every tile has to be reachable from where the empty cell starts, or the walls would keep
+        // some of them in place forever and the empty cell could even be shut in
+        let mut reached = vec![board.get_empty_index()];
+        let mut index = 0;
+
+        while let Some(cell) = reached.get(index).copied() {
+            for direction in (0..4).map(Direction::from) {
+                match board.get_neighbor_index(cell, direction) {
+                    Some(neighbor) if !reached.contains(&neighbor) => reached.push(neighbor),
+                    _ => {}
+                }
+            }
+
+            index += 1;
+        }
+
+        let open_cells = (0..board.get_cell_count()).filter(|cell| !board.walls.contains(cell)).count();
+
+        if reached.len() < open_cells {
+            return Err("the walls have to leave every cell connected to the last one".to_owned());
+        }
+
+        Ok(board)
+    }
+
+    // tiles with the same letter can't be told apart, so either one belongs in the other's place.
+    // the same goes for two empty cells
+    pub fn is_same_tile(&self, tile: i32, other: i32) -> bool {
+        match (self.is_empty_value(tile), self.is_empty_value(other)) {
+            (true, true) => true,
+            (false, false) => {
+                let letter = |tile: i32| (tile as usize).checked_sub(1).and_then(|i| self.letters.get(i));
+                tile == other || letter(tile).is_some_and(|letter_tile| letter(other) == Some(letter_tile))
+            }
+            _ => false,
+        }
+    }
+
+    pub fn solved(width: u8, height: u8) -> Board {
+        Board::solved_with_goal(width, height, Goal::RowMajor)
+    }
+
+    pub fn solved_with_goal(width: u8, height: u8, goal: Goal) -> Board {
+        let mut board = Board::new(goal.cells(width, height), width, height);
+        board.goal = goal;
+        board.check_solved();
+        board
+    }
+
+    // a solved board of another size, with the goal and the variant of this one. the walls of a
+    // level and a target don't fit another size, so they are left out
+    pub fn resized(&self, width: u8, height: u8) -> Board {
+        let mut board = Board {
+            cells: self.goal.cells(width, height),
+            width,
+            height,
+            second_blank: false,
+            walls: Vec::new(),
+            target: None,
+            ..self.clone()
+        };
+        board.check_solved();
+        board
+    }
+
+    pub fn goal_cells(&self) -> Vec<i32> {
+        match &self.target {
+            Some(target) => target.clone(),
+            None => self.goal.cells(self.width, self.height),
+        }
+    }
+
+    // the walking distance, pattern database and reduction solvers only know the usual goal
+    pub fn is_row_major(&self) -> bool {
+        self.goal == Goal::RowMajor && self.target.is_none()
+    }
+
+    // the index every value belongs at in the goal, the one of value v at v - 1
+    pub fn goal_indices(&self) -> Vec<usize> {
+        let mut indices = vec![0; self.get_cell_count()];
+
+        for (index, cell) in self.goal_cells().into_iter().enumerate() {
+            indices[cell as usize - 1] = index;
+        }
+
+        indices
+    }
+
+    // tiles that look the same can be in either of their goal positions
+    pub fn check_solved(&mut self) {
+        let goal_cells = self.goal_cells();
+        self.solved = (self.cells.iter().zip(&goal_cells)).all(|(cell, goal)| self.is_same_tile(*cell, *goal));
+    }
+
+    // puts any arrangement of the board's tiles in place, e.g. one made in the editor. the cells
+    // have to hold every value once, but the position doesn't have to be solvable
+    pub fn set_cells(&mut self, cells: Vec<i32>) -> Result<(), String> {
+        let mut board = Board::unchecked(cells, self.width, self.height);
+
+        if !board.is_valid() {
+            return Err(format!(
+                "a {}x{} position has to contain every number from 1 to {} and '_' exactly once",
+                self.width,
+                self.height,
+                self.get_cell_count() - 1
+            ));
+        }
+
+        board.goal = self.goal;
+        board.check_solved();
+        *self = board;
+        Ok(())
+    }
+
+    pub fn get_cell_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    // checks that the board holds every value from 1 to width * height exactly once,
+    // boards loaded from disk can't be trusted to
+    pub fn is_valid(&self) -> bool {
+        let mut cells = self.cells.clone();
+        cells.sort();
+        let goal_cells = self.goal_cells();
+
+        (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&self.width)
+            && (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&self.height)
+            && cells.into_iter().eq(1..self.get_cell_count() as i32 + 1)
+            && (self.walls.iter()).all(|wall| self.cells.get(*wall).is_some_and(|cell| *cell == goal_cells[*wall]))
+    }
+
+    // e.g. "123/456/78_", only for boards with fewer than 63 cells
+    pub fn to_compact_string(&self) -> Option<String> {
+        let compact_char = |cell: i32| {
+            if self.is_empty_value(cell) {
+                Some('_')
+            } else {
+                COMPACT_CHARS.chars().nth(cell as usize - 1)
+            }
+        };
+
+        let rows = self
+            .cells
+            .chunks(self.width as usize)
+            .map(|row| row.iter().map(|cell| compact_char(*cell)).collect::<Option<String>>())
+            .collect::<Option<Vec<String>>>()?;
+
+        Some(rows.join("/"))
+    }
+
+    // the empty cell is represented by the highest value on the board
+    pub fn get_empty_value(&self) -> i32 {
+        self.get_cell_count() as i32
+    }
+
+    // with two empty cells the second highest value is empty too
+    pub fn is_empty_value(&self, cell: i32) -> bool {
+        let empty_value = self.get_empty_value();
+        cell == empty_value || (self.two_blanks && cell == empty_value - 1)
+    }
+
+    // the empty cell that moves, the second one of two if it was switched to
+    pub fn get_empty_index(&self) -> usize {
+        let empty_value = self.get_empty_value() - (self.two_blanks && self.second_blank) as i32;
+        self.cells.iter().position(|cell| *cell == empty_value).unwrap()
+    }
+
+    // moves go to the other empty cell from now on, on boards with two
+    pub fn switch_blank(&mut self) {
+        if self.two_blanks {
+            self.second_blank = !self.second_blank;
+        }
+    }
+
+    pub fn get_neighbor_index(&self, index: usize, direction: Direction) -> Option<usize> {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let row = index / width;
+        let col = index % width;
+
+        // on a wrapping board the cells at the edges are neighbors of the ones across the board
+        let neighbor_index = match direction {
+            Direction::Up => {
+                if row == 0 {
+                    self.wrap.then_some((height - 1) * width + col)
+                } else {
+                    Some((row - 1) * width + col)
+                }
+            }
+            Direction::Down => {
+                if row == height - 1 {
+                    self.wrap.then_some(col)
+                } else {
+                    Some((row + 1) * width + col)
+                }
+            }
+            Direction::Left => {
+                if col == 0 {
+                    self.wrap.then_some(row * width + width - 1)
+                } else {
+                    Some(row * width + col - 1)
+                }
+            }
+            Direction::Right => {
+                if col == width - 1 {
+                    self.wrap.then_some(row * width)
+                } else {
+                    Some(row * width + col + 1)
+                }
+            }
+        };
+
+        // walls block the way like the edges of the board
+        neighbor_index.filter(|neighbor_index| !self.walls.contains(neighbor_index))
+    }
+
+    // returns the direction the empty cell has to move to reach the cell at index, or None if the
+    // cell is not in the same row or column as the empty cell or a wall is in the way
+    pub fn get_direction_to(&self, index: usize) -> Option<Direction> {
+        let width = self.width as usize;
+        let empty_index = self.get_empty_index();
+        let (empty_row, empty_col) = (empty_index / width, empty_index % width);
+        let (row, col) = (index / width, index % width);
+
+        let direction = if row == empty_row && col < empty_col {
+            Direction::Left
+        } else if row == empty_row && col > empty_col {
+            Direction::Right
+        } else if col == empty_col && row < empty_row {
+            Direction::Up
+        } else if col == empty_col && row > empty_row {
+            Direction::Down
+        } else {
+            return None;
+        };
+
+        let mut cell = empty_index;
+
+        while cell != index {
+            cell = self.get_neighbor_index(cell, direction)?;
+        }
+
+        Some(direction)
+    }
+
+    // slides every tile between the empty cell and the cell at index towards the empty cell,
+    // returns each move of the empty cell that was performed. with two empty cells, the other one
+    // is switched to if only it is in line with the tile
+    pub fn move_tile_at(&mut self, index: usize) -> Vec<Direction> {
+        let mut moves = Vec::new();
+
+        if self.two_blanks && self.get_direction_to(index).is_none() {
+            self.switch_blank();
+
+            if self.get_direction_to(index).is_none() {
+                self.switch_blank();
+            }
+        }
+
+        if let Some(direction) = self.get_direction_to(index) {
+            while self.get_empty_index() != index && self.move_empty(direction) {
+                moves.push(direction);
+            }
+        }
+
+        moves
+    }
+
+    // lets the tiles above the empty cell fall into it one after the other in the gravity variant,
+    // returns the moves of the empty cell as it rises. a board that wraps around stops short of
+    // bringing the empty cell back to where it started
+    pub fn settle(&mut self) -> Vec<Direction> {
+        let mut falls = Vec::new();
+
+        while self.gravity && falls.len() + 1 < self.height as usize && self.move_empty(Direction::Up) {
+            falls.push(Direction::Up);
+        }
+
+        falls
+    }
+
+    // returns false if the move was rejected because the empty cell is at the edge of the board, or
+    // next to the other empty cell since swapping those wouldn't move a tile
+    pub fn move_empty(&mut self, direction: Direction) -> bool {
+        let empty_index = self.get_empty_index();
+        let neighbor_index = (self.get_neighbor_index(empty_index, direction))
+            .filter(|neighbor_index| !self.is_empty_value(self.cells[*neighbor_index]));
+
+        if let Some(neighbor_index) = neighbor_index {
+            self.cells.swap(empty_index, neighbor_index);
+        }
+
+        self.check_solved();
+        neighbor_index.is_some()
+    }
+}
+
+// rows are separated by '/' and the empty cell is written as '_', e.g. "1 2 3 / 4 5 6 / 7 8 _"
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let rows: Vec<String> = self
+            .cells
+            .chunks(self.width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        if self.is_empty_value(*cell) {
+                            "_".to_owned()
+                        } else {
+                            cell.to_string()
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect();
+
+        write!(f, "{}", rows.join(" / "))
+    }
+}
+
+// reads both the format written by Display and the compact format. a position written without '/'
+// has to be square
+impl std::str::FromStr for Board {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Board, String> {
+        let rows: Vec<Vec<String>> = value
+            .trim()
+            .split('/')
+            .map(|row| {
+                let cells: Vec<&str> = row
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|cell| !cell.is_empty())
+                    .collect();
+
+                // a single cell can't be a row, so it has to be a compact row
+                match cells.as_slice() {
+                    [compact_row] if compact_row.chars().count() > 1 => compact_row
+                        .chars()
+                        .map(|c| match COMPACT_CHARS.find(c) {
+                            Some(index) => (index + 1).to_string(),
+                            None => c.to_string(),
+                        })
+                        .collect(),
+                    _ => cells.iter().map(|cell| cell.to_string()).collect(),
+                }
+            })
+            .collect();
+
+        let (width, height) = if rows.len() == 1 {
+            let side = (rows[0].len() as f64).sqrt() as usize;
+
+            if side * side != rows[0].len() {
+                return Err("a position without rows has to be square".to_owned());
+            }
+
+            (side, side)
+        } else {
+            let width = rows[0].len();
+
+            if rows.iter().any(|row| row.len() != width) {
+                return Err("every row has to have the same number of cells".to_owned());
+            }
+
+            (width, rows.len())
+        };
+
+        if width > MAX_BOARD_SIZE as usize || height > MAX_BOARD_SIZE as usize {
+            return Err(format!("board sides can be at most {}", MAX_BOARD_SIZE));
+        }
+
+        let empty_value = (width * height) as i32;
+        let cells = rows
+            .concat()
+            .into_iter()
+            .map(|cell| match cell.as_str() {
+                "_" => Ok(empty_value),
+                _ => cell.parse::<i32>().map_err(|_| format!("invalid cell: {}", cell)),
+            })
+            .collect::<Result<Vec<i32>, String>>()?;
+
+        let mut board = Board::unchecked(cells, width as u8, height as u8);
+
+        if !board.is_valid() {
+            return Err(format!(
+                "a {}x{} position has to contain every number from 1 to {} and '_' exactly once",
+                width,
+                height,
+                empty_value - 1
+            ));
+        }
+
+        board.check_solved();
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(position: &str) -> Board {
+        position.parse().unwrap()
+    }
+
+    #[test]
+    fn seeded_scrambles_are_reproducible() {
+        for difficulty in Difficulty::ALL {
+            let mut first = Board::solved(4, 4);
+            let mut second = Board::solved(4, 4);
+            let first_moves = first.scramble(difficulty, 1234).unwrap();
+            let second_moves = second.scramble(difficulty, 1234).unwrap();
+
+            assert!(first.cells == second.cells);
+            assert!(first_moves == second_moves);
+        }
+    }
+
+    #[test]
+    fn scrambles_are_solvable_and_follow_their_moves() {
+        for difficulty in Difficulty::ALL {
+            for seed in 0..20 {
+                let mut board = Board::solved(4, 4);
+                let moves = board.scramble(difficulty, seed).unwrap();
+
+                assert!(board.is_solvable());
+                assert!(!board.solved);
+
+                let mut replayed = Board::solved(4, 4);
+                assert!(moves.iter().all(|direction| replayed.move_empty(*direction)));
+                assert!(replayed.cells == board.cells);
+            }
+        }
+    }
+
+    #[test]
+    fn easy_scrambles_are_short() {
+        for seed in 0..20 {
+            let mut board = Board::solved(3, 3);
+            board.scramble(Difficulty::Easy, seed).unwrap();
+
+            let heuristic = solver::SolverHeuristic::Manhattan;
+            let solution = solver::solve(&board, heuristic, u64::MAX, &AtomicBool::new(false)).unwrap();
+            assert!(!solution.is_empty() && solution.len() < EASY_MAX_MOVES);
+        }
+    }
+
+    #[test]
+    fn display_round_trips_through_from_str() {
+        for seed in 0..10 {
+            let mut board = Board::solved(5, 3);
+            board.scramble(Difficulty::Random, seed).unwrap();
+
+            let parsed = parse(&board.to_string());
+            assert!(parsed.cells == board.cells);
+            assert!((parsed.width, parsed.height) == (5, 3));
+
+            let compact = parse(&board.to_compact_string().unwrap());
+            assert!(compact.cells == board.cells);
+        }
+    }
+
+    #[test]
+    fn from_str_rejects_invalid_positions() {
+        for position in ["1 2 3 / 4 5 6 / 7 8 0", "1 2 3 / 4 5 6 / 7 7 _", "1 2 / 3 _", "1 2 3 / 4 5 6 / 7 x _"] {
+            assert!(position.parse::<Board>().is_err(), "{}", position);
+        }
+    }
+
+    #[test]
+    fn solvability_follows_the_parity() {
+        // odd width, the parity of the tiles alone decides
+        assert!(parse("1 2 3 / 4 5 6 / 7 _ 8").is_solvable());
+        assert!(!parse("1 2 3 / 4 5 6 / 8 7 _").is_solvable());
+        assert!(!parse("2 1 3 / 4 5 6 / 7 8 _").is_solvable());
+
+        // even width, the row of the empty cell counts as well
+        assert!(parse("1 2 3 4 / 5 6 7 8 / 9 10 11 _ / 13 14 15 12").is_solvable());
+        assert!(!parse("1 2 3 4 / 5 6 7 8 / 9 10 11 12 / 13 15 14 _").is_solvable());
+        assert!(!parse("1 2 3 4 / 5 6 7 8 / 9 10 11 _ / 13 14 12 15").is_solvable());
+    }
+
+    #[test]
+    fn move_tile_at_slides_the_row() {
+        let mut board = Board::solved(4, 4);
+        let moves = board.move_tile_at(12);
+
+        assert!(moves.len() == 3 && moves.iter().all(|direction| *direction == Direction::Left));
+        assert!(board.cells[12..] == [16, 13, 14, 15]);
+        assert!(board.get_empty_index() == 12);
+
+        // back the other way
+        let moves = board.move_tile_at(15);
+        assert!(moves.len() == 3 && moves.iter().all(|direction| *direction == Direction::Right));
+        assert!(board.solved);
+    }
+
+    #[test]
+    fn move_tile_at_ignores_tiles_off_the_row_and_column() {
+        let mut board = Board::solved(4, 4);
+
+        assert!(board.move_tile_at(0).is_empty());
+        assert!(board.solved);
+    }
+}
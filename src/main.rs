@@ -1,250 +1,3433 @@
+use clap::Parser;
+use fifteen_puzzle_rust::{
+    notation, parse_board_size, solver, Board, Difficulty, Direction, Goal, MAX_BOARD_SIZE, MIN_BOARD_SIZE,
+};
 use rand::prelude::*;
 use raylib::prelude::*;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+mod animation;
+mod audio;
+mod confetti;
+mod config;
+mod daily;
+mod editor;
+mod events;
+mod font;
+mod input;
+mod inspection;
+mod level;
+mod menu;
+mod multiplayer;
+mod online;
+mod picture;
+mod race;
+mod renderer;
+mod replay;
+mod save;
+mod screenshot;
+mod settings;
+mod share;
+mod splits;
+mod state;
+mod stats;
+mod storage;
+mod streak;
+mod themes;
+mod twitch;
+mod versus;
+
+use animation::TileAnimation;
+use config::{Config, WindowConfig};
+use events::{Event, EventBus, Subscriber};
+use input::{Action, Button, Input, TouchGesture};
+use picture::Picture;
+use renderer::{Rect, Renderer};
+use state::GameState;
+
+pub const MESSAGE_WINDOW_WIDTH: f32 = 400.0;
+pub const MESSAGE_WINDOW_HEIGHT: f32 = 240.0;
+// the window can't be resized smaller than the message windows, the height is without the hud
+pub const MIN_WINDOW_WIDTH: i32 = MESSAGE_WINDOW_WIDTH as i32;
+pub const MIN_WINDOW_HEIGHT: i32 = MESSAGE_WINDOW_HEIGHT as i32;
+// enough for any 8 puzzle and most 15 puzzles, hints should show up within a few seconds
+pub const HINT_MAX_NODES: u64 = 5_000_000;
+// auto-solve is asked for explicitly, so it is allowed to take a while to find a solution
+pub const AUTO_SOLVE_MAX_NODES: u64 = 200_000_000;
+// the rows the endgame trainer scrambles, the rest of the board is solved
+pub const ENDGAME_ROWS: u8 = 2;
+// the marathon starts on the smallest board and ends with this size
+pub const MARATHON_LAST_SIZE: u8 = 8;
+// how long a toast like the one for a saved screenshot stays on screen
+pub const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+// where a board is drawn in the window. it is worked out from the size of the window every frame,
+// so the board keeps fitting the window as it is resized
+#[derive(Clone, Copy)]
+struct Layout {
+    x: i32,
+    y: i32,
+    cell_size: i32,
+}
+
+impl Layout {
+    // the biggest square cells that fit the board into the area, with the board centered in it
+    fn new(board: &Board, area: Rectangle) -> Layout {
+        let cell_size = (area.width as i32 / board.width as i32)
+            .min(area.height as i32 / board.height as i32)
+            .max(1);
+
+        Layout {
+            x: area.x as i32 + (area.width as i32 - cell_size * board.width as i32) / 2,
+            y: area.y as i32 + (area.height as i32 - cell_size * board.height as i32) / 2,
+            cell_size,
+        }
+    }
+
+    // the top left corner of the cell at index
+    fn cell_position(&self, index: usize, width: u8) -> (i32, i32) {
+        (
+            self.x + (index % width as usize) as i32 * self.cell_size,
+            self.y + (index / width as usize) as i32 * self.cell_size,
+        )
+    }
+
+    // the index of the board's cell under the position, if there is one
+    fn get_index_at(&self, board: &Board, position: Vector2) -> Option<usize> {
+        let (x, y) = (position.x - self.x as f32, position.y - self.y as f32);
+
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        let col = x as usize / self.cell_size as usize;
+        let row = y as usize / self.cell_size as usize;
+
+        if col >= board.width as usize || row >= board.height as usize {
+            return None;
+        }
+
+        Some(row * board.width as usize + col)
+    }
+}
+
+// which tiles of the board can be made out
+#[derive(Clone, Copy, PartialEq)]
+enum Visibility {
+    All,
+    // none of them, in blindfold mode
+    Blindfolded,
+    // only the ones at most this many moves from the empty cell, in fog of war
+    Fog(u8),
+}
+
+// with a picture the tiles show their part of it rather than their number
+fn draw_board<R: Renderer>(
+    d: &mut R,
+    board: &Board,
+    layout: Layout,
+    animation: &TileAnimation,
+    picture: Option<&R::Picture>,
+    visibility: Visibility,
+    config: &Config,
+) {
+    let colors = &config.colors;
+    let cell_size = layout.cell_size;
+    // the numbers grow with the tiles, and are centered on them
+    let font_size = (cell_size * 2 / 5).max(10);
+    let bold_offset = (font_size / 20).max(1);
+    // the tiles are see-through while the goal is shown, so it can be made out underneath them
+    let tile_alpha = if config.goal_overlay { 0.75 } else { 1.0 };
+
+    let goal_cells = board.goal_cells();
+    let empty_index = board.get_empty_index();
+
+    // every cell starts out empty, the tiles are drawn over them where they currently are
+    for (i, goal) in goal_cells.iter().copied().enumerate() {
+        let (x, y) = layout.cell_position(i, board.width);
+
+        let cell = Rect::from((x, y, cell_size, cell_size));
+        d.draw_panel(cell, colors.background_darker);
+        d.draw_outline(cell, 1.0, colors.border);
+
+        // with two empty cells, the one that moves is outlined
+        if board.two_blanks && i == empty_index {
+            d.draw_outline(cell, 3.0, colors.text.fade(0.5));
+        }
+
+        // the tile that belongs in the cell, faded
+        if config.goal_overlay && !board.is_empty_value(goal) && !board.walls.contains(&i) {
+            if picture.is_some() {
+                d.draw_tile(cell, goal, (board.width, board.height), picture, Color::WHITE.fade(0.25));
+            } else {
+                let number = board.tile_label(goal);
+                let text_x = x + (cell_size - d.measure_text(&number, font_size)) / 2;
+                let text_y = y + (cell_size - font_size) / 2;
+                d.draw_text(&number, text_x, text_y, font_size, colors.text.fade(0.25));
+            }
+        }
+    }
+
+    for (i, cell) in board.cells.iter().enumerate() {
+        if board.is_empty_value(*cell) {
+            continue;
+        }
+
+        let (column, row) = animation.position(*cell, i, board.width);
+        let x = layout.x + (column * cell_size as f32) as i32;
+        let y = layout.y + (row * cell_size as f32) as i32;
+
+        let is_correct = board.is_same_tile(*cell, goal_cells[i]);
+        let tile = Rect::from((x, y, cell_size, cell_size));
+
+        // walls are solid blocks, with nothing to tell about them
+        if board.walls.contains(&i) {
+            d.draw_panel(tile, colors.border);
+            continue;
+        }
+
+        // tiles that can't be seen are plain squares that don't tell where they belong, the ones in
+        // the fog are dimmed as well
+        let hidden = match visibility {
+            Visibility::All => None,
+            Visibility::Blindfolded => Some(colors.background),
+            Visibility::Fog(radius) => {
+                let (row, column) = (i / board.width as usize, i % board.width as usize);
+                let distance = (empty_index / board.width as usize).abs_diff(row)
+                    + (empty_index % board.width as usize).abs_diff(column);
+                (distance > radius as usize).then_some(colors.background.fade(0.3))
+            }
+        };
+
+        if let Some(color) = hidden {
+            d.draw_panel(tile, color);
+            d.draw_outline(tile, colors.tile_border_width, colors.border);
+            continue;
+        }
+
+        let color = if picture.is_some() { Color::WHITE } else { colors.background };
+        d.draw_tile(tile, *cell, (board.width, board.height), picture, color.fade(tile_alpha));
+
+        if let Some((correct, misplaced)) = config.correctness_colors.tints() {
+            let tint = if is_correct { correct } else { misplaced };
+            d.draw_panel(tile, tint.fade(0.35));
+        }
+
+        if config.correctness_symbols {
+            draw_correctness_symbol(d, x, y, cell_size, is_correct, colors.text);
+        }
+
+        d.draw_outline(tile, colors.tile_border_width, colors.border);
+
+        let number = board.tile_label(*cell);
+
+        // numbers would cover most of a picture, so they are small and in the corner if shown at all
+        if picture.is_some() {
+            if config.image_numbers {
+                let label_size = (font_size / 2).max(10);
+                let label_width = d.measure_text(&number, label_size) + 8;
+                let label = Rect::new(x as f32 + 4.0, y as f32 + 4.0, label_width as f32, label_size as f32 + 6.0);
+                d.draw_panel(label, colors.background.fade(0.8));
+                d.draw_text(&number, x + 8, y + 7, label_size, colors.text);
+            }
+
+            continue;
+        }
+
+        let text_x = x + (cell_size - d.measure_text(&number, font_size)) / 2;
+        let text_y = y + (cell_size - font_size) / 2;
+
+        // raylib's font has no bold, so the number is drawn again slightly to the right
+        if colors.bold_numbers {
+            d.draw_text(&number, text_x + bold_offset, text_y, font_size, colors.text);
+        }
+
+        d.draw_text(&number, text_x, text_y, font_size, colors.text);
+    }
+}
+
+// what the running solver job was started for
+#[derive(Clone, Copy)]
+enum SolvePurpose {
+    Hint,
+    AutoSolve,
+    // copies the solution to the clipboard
+    Export,
+}
+
+// measures play time, only counting the time between start and stop
+struct Timer {
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Timer {
+    fn new() -> Timer {
+        Timer::with_elapsed(Duration::ZERO)
+    }
+
+    // a stopped timer that already counted some time, e.g. from a saved game
+    fn with_elapsed(elapsed: Duration) -> Timer {
+        Timer {
+            elapsed,
+            running_since: None,
+        }
+    }
+
+    fn start(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(running_since) = self.running_since.take() {
+            self.elapsed += running_since.elapsed();
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Timer::new();
+    }
+
+    fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(running_since) => self.elapsed + running_since.elapsed(),
+            None => self.elapsed,
+        }
+    }
+}
+
+// formats a duration as minutes:seconds.milliseconds, e.g. 01:23.456
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    format!("{:02}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+// the blindfold mode, where the numbers are hidden once the board had some time to be memorized
+struct Blindfold {
+    memorize_time: Duration,
+    shown_since: Instant,
+    // set once the player looked at the board, which ends the attempt
+    revealed: bool,
+}
+
+impl Blindfold {
+    fn new(memorize_time: Duration) -> Blindfold {
+        Blindfold {
+            memorize_time,
+            shown_since: Instant::now(),
+            revealed: false,
+        }
+    }
+}
+
+// time attack, where every board solved before the clock runs out earns more time
+struct TimeAttack {
+    time_limit: Duration,
+    bonus: Duration,
+    // the time there is to play, the time limit and the bonus of every solve
+    time: Duration,
+    // only runs while a board is being played
+    clock: Timer,
+    boards_solved: u32,
+}
+
+impl TimeAttack {
+    fn new(time_limit: Duration, bonus: Duration) -> TimeAttack {
+        TimeAttack {
+            time_limit,
+            bonus,
+            time: time_limit,
+            clock: Timer::new(),
+            boards_solved: 0,
+        }
+    }
+
+    fn time_left(&self) -> Duration {
+        self.time.saturating_sub(self.clock.elapsed())
+    }
+
+    fn is_over(&self) -> bool {
+        self.time_left().is_zero()
+    }
+
+    fn board_solved(&mut self) {
+        self.boards_solved += 1;
+        self.time += self.bonus;
+    }
+}
+
+// the marathon, one board of every size from the smallest up, timed all together
+struct Marathon {
+    // the size, time and moves of every board solved so far
+    solves: Vec<(u8, Duration, u32)>,
+}
+
+impl Marathon {
+    fn new() -> Marathon {
+        Marathon { solves: Vec::new() }
+    }
+
+    // the size of the board after the last one solved, the marathon starts over once it is finished
+    fn next_size(&self) -> u8 {
+        match self.solves.last() {
+            Some((size, _, _)) if *size < MARATHON_LAST_SIZE => size + 1,
+            _ => MIN_BOARD_SIZE,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.solves.last().is_some_and(|(size, _, _)| *size >= MARATHON_LAST_SIZE)
+    }
+
+    fn total_time(&self) -> Duration {
+        self.solves.iter().map(|(_, time, _)| *time).sum()
+    }
+}
+
+// the streak mode, counting the boards solved in a row within the target time
+#[derive(Clone, Copy)]
+struct Streak {
+    target: Duration,
+    current: u32,
+    best: u32,
+}
+
+// the fewest moves that reach the target of the target mode, worked out once it is reached, with
+// whether it is only a lower bound
+struct TargetScore {
+    job: Option<solver::SolverJob>,
+    optimal: Option<(u32, bool)>,
+    heuristic: solver::SolverHeuristic,
+}
+
+impl TargetScore {
+    fn new(heuristic: solver::SolverHeuristic) -> TargetScore {
+        TargetScore {
+            job: None,
+            optimal: None,
+            heuristic,
+        }
+    }
+
+    fn update(&mut self, game: &Game) {
+        if let Some(job) = self.job.take() {
+            match job.poll() {
+                Some(Some(solution)) => self.optimal = Some((solution.len() as u32, false)),
+                Some(None) => self.optimal = Some((solver::manhattan_distance(job.board()), true)),
+                None => self.job = Some(job),
+            }
+        }
+
+        // a new board was started before the fewest moves were known
+        if !game.board.solved {
+            if let Some(job) = self.job.take() {
+                job.cancel();
+            }
+
+            self.optimal = None;
+        }
+    }
+}
+
+impl Subscriber for TargetScore {
+    // the moves are scored against the fewest possible
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        if matches!(event, Event::MoveApplied { solved: true, .. }) && game.target && game.playback.is_none() {
+            self.job = Some(solver::SolverJob::start(&game.start_board(), self.heuristic, AUTO_SOLVE_MAX_NODES));
+        }
+    }
+}
+
+// a level being played, along with the player's progress on it
+struct Game {
+    board: Board,
+    move_count: u32,
+    // undos are kept track of separately so timed modes can penalize them
+    undo_count: u32,
+    timer: Timer,
+    history: Vec<Direction>,
+    redo_history: Vec<Direction>,
+    // for boards with two empty cells, whether each move of the history (and the redo history) was
+    // made by the second one
+    blanks: Vec<bool>,
+    redo_blanks: Vec<bool>,
+    // the seed the board was scrambled with, None for positions that were not generated
+    seed: Option<u64>,
+    // the rest of the solution being played back by auto-solve, in reverse order
+    auto_solve_moves: Vec<Direction>,
+    // set when auto-solve finished the board rather than the player
+    auto_solved: bool,
+    // set once twitch chat made a move on the board, so it isn't the player's solve alone
+    chat_moved: bool,
+    // when each phase of the solve was finished, see splits::phases
+    splits: Vec<Duration>,
+    // every move of the empty cell with its time, undos included
+    replay_moves: Vec<replay::ReplayMove>,
+    // set when the game is a replay being watched rather than played
+    playback: Option<replay::Playback>,
+    // the board as it was scrambled, for trying the same scramble again
+    start: Board,
+    // the moves of the last scramble, to show the scramble being played
+    scramble_moves: Vec<Direction>,
+    // set for the endgame trainer, whose boards are solved but for the last rows
+    endgame: bool,
+    blindfold: Option<Blindfold>,
+    // set for fog of war, where only the tiles around the empty cell can be seen
+    fog: bool,
+    // kept from board to board, the time attack only ends once its clock runs out
+    time_attack: Option<TimeAttack>,
+    marathon: Option<Marathon>,
+    // set for zen mode, which has no timer or move counter and goes on to the next board by itself
+    zen: bool,
+    // kept from board to board, giving up on a board breaks the streak
+    streak: Option<Streak>,
+    // set for the target mode, where the board starts solved and a scramble of it has to be reached
+    target: bool,
+    // when the tiles above the empty cell fall into it, in the gravity variant
+    falls_due: Option<Instant>,
+    events: EventBus,
+}
+
+impl Game {
+    fn new(board: Board) -> Game {
+        Game {
+            start: board.clone(),
+            board,
+            move_count: 0,
+            undo_count: 0,
+            timer: Timer::new(),
+            history: Vec::new(),
+            redo_history: Vec::new(),
+            blanks: Vec::new(),
+            redo_blanks: Vec::new(),
+            seed: None,
+            auto_solve_moves: Vec::new(),
+            auto_solved: false,
+            chat_moved: false,
+            splits: Vec::new(),
+            replay_moves: Vec::new(),
+            playback: None,
+            scramble_moves: Vec::new(),
+            endgame: false,
+            blindfold: None,
+            fog: false,
+            time_attack: None,
+            marathon: None,
+            zen: false,
+            streak: None,
+            target: false,
+            falls_due: None,
+            events: EventBus::new(),
+        }
+    }
+
+    // auto-solved boards, replays, training positions, variants and other goals don't count towards
+    // the stats
+    fn counts_for_stats(&self) -> bool {
+        let variant = self.blindfold.is_some() || self.fog || self.zen || self.board.is_variant();
+        let practice = self.endgame || variant || !self.board.is_row_major();
+        !self.auto_solved && !self.chat_moved && self.playback.is_none() && !practice
+    }
+
+    // the time left to memorize a blindfold board, the first move ends it early
+    fn memorize_time_left(&self) -> Option<Duration> {
+        let blindfold = self.blindfold.as_ref()?;
+        let left = blindfold.memorize_time.saturating_sub(blindfold.shown_since.elapsed());
+        (self.move_count == 0 && !left.is_zero()).then_some(left)
+    }
+
+    // whether the numbers are hidden, from the end of the memorizing until the board is solved or
+    // revealed
+    fn is_blindfolded(&self) -> bool {
+        self.blindfold.as_ref().is_some_and(|blindfold| !blindfold.revealed)
+            && self.memorize_time_left().is_none()
+            && !self.board.solved
+    }
+
+    fn is_time_up(&self) -> bool {
+        self.time_attack.as_ref().is_some_and(|time_attack| time_attack.is_over())
+    }
+
+    // the whole board can be seen once it is solved
+    fn visibility(&self, config: &Config) -> Visibility {
+        if self.is_blindfolded() {
+            Visibility::Blindfolded
+        } else if self.fog && !self.board.solved {
+            Visibility::Fog(config.fog_radius)
+        } else {
+            Visibility::All
+        }
+    }
+
+    // the tiles in their goal position and the number of tiles, once a blindfold board that isn't
+    // solved was revealed
+    fn blindfold_score(&self) -> Option<(usize, usize)> {
+        self.blindfold.as_ref().filter(|blindfold| blindfold.revealed && !self.board.solved)?;
+
+        let board = &self.board;
+        let goal_cells = board.goal_cells();
+        let in_place = (board.cells.iter().zip(&goal_cells))
+            .filter(|(cell, goal)| !board.is_empty_value(**cell) && cell == goal)
+            .count();
+        let tiles = board.cells.iter().filter(|cell| !board.is_empty_value(**cell)).count();
+
+        Some((in_place, tiles))
+    }
+
+    // gives up on solving a blindfold board from memory and shows it
+    fn reveal(&mut self) {
+        if let Some(blindfold) = self.blindfold.as_mut() {
+            blindfold.revealed = true;
+            self.timer.stop();
+        }
+    }
+
+    fn from_replay(replay: replay::Replay, speed: f32) -> Game {
+        let playback = replay::Playback::new(replay, speed);
+        let mut game = Game::new(playback.start_board());
+        game.seed = playback.seed();
+        game.playback = Some(playback);
+        game
+    }
+
+    // scrambles with the given seed, or a random one. random seeds are kept short so they
+    // are easy to share
+    fn scramble(&mut self, difficulty: Difficulty, seed: Option<u64>) {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().gen_range(0..100_000_000));
+        self.scramble_moves = if self.endgame {
+            self.board.scramble_endgame(ENDGAME_ROWS, seed)
+        } else if self.target {
+            // the board isn't scrambled at all, so there is nothing to show
+            self.board.scramble_target(difficulty, seed);
+            Vec::new()
+        } else {
+            self.board.scramble(difficulty, seed).unwrap_or_else(|error| {
+                eprintln!("could not scramble the board: {}", error);
+                Vec::new()
+            })
+        };
+        self.seed = Some(seed);
+        self.start = self.board.clone();
+
+        self.move_count = 0;
+        self.undo_count = 0;
+        self.timer.reset();
+        self.history.clear();
+        self.redo_history.clear();
+        self.blanks.clear();
+        self.redo_blanks.clear();
+        self.auto_solve_moves.clear();
+        self.auto_solved = false;
+        self.chat_moved = false;
+        self.splits.clear();
+        self.replay_moves.clear();
+        self.playback = None;
+        self.falls_due = None;
+
+        if let Some(blindfold) = self.blindfold.as_mut() {
+            *blindfold = Blindfold::new(blindfold.memorize_time);
+        }
+
+        self.events.publish(Event::ScrambleStarted);
+    }
+
+    // the same scramble again from the start, in the same mode
+    fn restart(self) -> Game {
+        let mut game = Game::new(self.start);
+        game.seed = self.seed;
+        game.endgame = self.endgame;
+        // the board is shown to memorize again
+        game.blindfold = self.blindfold.map(|blindfold| Blindfold::new(blindfold.memorize_time));
+        game.fog = self.fog;
+        game.time_attack = self.time_attack;
+        game.marathon = self.marathon;
+        game.zen = self.zen;
+        game.streak = self.streak.map(|streak| Streak { current: 0, ..streak });
+        game.target = self.target;
+        game.events.publish(Event::ScrambleStarted);
+        game
+    }
+
+    fn move_empty(&mut self, direction: Direction) -> bool {
+        // a solved board is left as it is until the next one
+        if self.board.solved {
+            return false;
+        }
+
+        // the tiles of the move before fall first
+        self.settle();
+        let moved = self.board.move_empty(direction);
+        let solved = moved && self.board.solved;
+        self.events.publish(Event::MoveApplied { moved, solved });
+
+        if !moved {
+            return false;
+        }
+
+        self.history.push(direction);
+        self.redo_history.clear();
+        self.blanks.push(self.board.second_blank);
+        self.redo_blanks.clear();
+        self.move_count += 1;
+        self.update_timer();
+        self.record_moves(&[direction]);
+        self.start_falls();
+        true
+    }
+
+    fn move_tile_at(&mut self, index: usize) -> bool {
+        if self.board.solved {
+            return false;
+        }
+
+        self.settle();
+        let moves = self.board.move_tile_at(index);
+        let (moved, solved) = (!moves.is_empty(), self.board.solved);
+        self.events.publish(Event::MoveApplied { moved, solved });
+
+        if moves.is_empty() {
+            return false;
+        }
+
+        self.move_count += moves.len() as u32;
+        self.redo_history.clear();
+        self.blanks.extend(std::iter::repeat_n(self.board.second_blank, moves.len()));
+        self.redo_blanks.clear();
+        self.update_timer();
+        self.record_moves(&moves);
+        self.history.extend(moves);
+        self.start_falls();
+        true
+    }
+
+    // with gravity, the tiles that fell after a move are taken back and fall again together with
+    // it. they are the only moves up, the empty cell can't get any higher than they take it
+    fn undo(&mut self) {
+        self.falls_due = None;
+
+        while self.board.gravity && self.history.last() == Some(&Direction::Up) {
+            self.take_back();
+        }
+
+        if self.take_back() {
+            self.move_count -= 1;
+            self.undo_count += 1;
+            self.update_timer();
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.make_again() {
+            self.move_count += 1;
+            self.update_timer();
+        }
+
+        while self.board.gravity && self.redo_history.last() == Some(&Direction::Up) {
+            self.make_again();
+        }
+    }
+
+    // moves are taken back and made again by the empty cell that made them
+    fn take_back(&mut self) -> bool {
+        let direction = match self.history.pop() {
+            Some(direction) => direction,
+            None => return false,
+        };
+
+        self.board.second_blank = self.blanks.pop().unwrap_or_default();
+        self.board.move_empty(direction.opposite());
+        self.redo_history.push(direction);
+        self.redo_blanks.push(self.board.second_blank);
+        self.record_moves(&[direction.opposite()]);
+        true
+    }
+
+    fn make_again(&mut self) -> bool {
+        let direction = match self.redo_history.pop() {
+            Some(direction) => direction,
+            None => return false,
+        };
+
+        self.board.second_blank = self.redo_blanks.pop().unwrap_or_default();
+        self.board.move_empty(direction);
+        self.history.push(direction);
+        self.blanks.push(self.board.second_blank);
+        self.record_moves(&[direction]);
+        true
+    }
+
+    // the tiles above the empty cell fall into it once the move that left it there has slid, in the
+    // gravity variant. falls aren't counted as moves
+    fn update_gravity(&mut self) {
+        if self.falls_due.is_some_and(|due| due <= Instant::now()) {
+            self.settle();
+        }
+    }
+
+    fn settle(&mut self) {
+        if self.falls_due.take().is_none() {
+            return;
+        }
+
+        let falls = self.board.settle();
+
+        if falls.is_empty() {
+            return;
+        }
+
+        self.events.publish(Event::MoveApplied { moved: true, solved: self.board.solved });
+        self.redo_history.clear();
+        self.blanks.extend(std::iter::repeat_n(self.board.second_blank, falls.len()));
+        self.redo_blanks.clear();
+        self.update_timer();
+        self.record_moves(&falls);
+        self.history.extend(falls);
+    }
+
+    // a move makes the tiles fall after it, unless it is one of a replay being watched, which has
+    // the falls among its moves
+    fn start_falls(&mut self) {
+        if self.board.gravity && self.playback.is_none() {
+            self.falls_due = Some(Instant::now() + animation::SLIDE_DURATION);
+        }
+    }
+
+    // the board as it was scrambled, found by taking back every move
+    fn start_board(&self) -> Board {
+        let mut board = self.board.clone();
+
+        for (direction, second_blank) in self.history.iter().zip(&self.blanks).rev() {
+            board.second_blank = *second_blank;
+            board.move_empty(direction.opposite());
+        }
+
+        board
+    }
+
+    fn start_auto_solve(&mut self, mut moves: Vec<Direction>) {
+        moves.reverse();
+        self.auto_solve_moves = moves;
+    }
+
+    fn stop_auto_solve(&mut self) {
+        self.auto_solve_moves.clear();
+    }
+
+    fn is_auto_solving(&self) -> bool {
+        !self.auto_solve_moves.is_empty()
+    }
+
+    // plays the next move of the auto-solve solution
+    fn step_auto_solve(&mut self) {
+        if let Some(direction) = self.auto_solve_moves.pop() {
+            self.move_empty(direction);
+            self.auto_solved = self.board.solved;
+        }
+    }
+
+    // applies the moves of the replay being watched that are due after frame_time
+    fn step_playback(&mut self, frame_time: Duration) {
+        let moves = match self.playback.as_mut() {
+            Some(playback) => playback.advance(frame_time),
+            None => return,
+        };
+
+        for direction in moves {
+            self.move_empty(direction);
+        }
+
+        // the timer shows the time into the replay rather than the time spent watching
+        if let Some(playback) = &self.playback {
+            self.timer = Timer::with_elapsed(playback.clock());
+        }
+    }
+
+    fn record_moves(&mut self, moves: &[Direction]) {
+        let time_ms = self.timer.elapsed().as_millis() as u64;
+
+        self.replay_moves.extend(moves.iter().map(|direction| replay::ReplayMove {
+            direction: *direction,
+            time_ms,
+        }));
+    }
+
+    // the timer starts with the first move after a scramble and stops once the board is solved
+    fn update_timer(&mut self) {
+        self.timer.start();
+
+        if self.board.solved {
+            self.timer.stop();
+        }
+
+        self.record_splits();
+    }
+
+    // a phase's split is taken the first time it is finished, once the phases before it are
+    fn record_splits(&mut self) {
+        let phases = splits::phases(self.board.width, self.board.height);
+
+        while phases.get(self.splits.len()).is_some_and(|phase| phase.is_done(&self.board)) {
+            self.splits.push(self.timer.elapsed());
+        }
+    }
+}
+
+fn format_window_title(board: &Board, level_index: i32) -> String {
+    (board.get_cell_count() - 1).to_string() + " Puzzle - Level " + &(level_index + 1).to_string()
+}
+
+fn message_window_bounds(screen_width: i32, screen_height: i32) -> Rectangle {
+    // narrow boards (e.g. 3x10) can be smaller than the message window
+    let width = MESSAGE_WINDOW_WIDTH.min(screen_width as f32);
+    let height = MESSAGE_WINDOW_HEIGHT.min(screen_height as f32);
+
+    Rectangle::new(
+        (screen_width as f32 - width) / 2.0,
+        (screen_height as f32 - height) / 2.0,
+        width,
+        height,
+    )
+}
+
+// command line arguments override the config file
+#[derive(Parser)]
+#[command(version, about = "A sliding puzzle game")]
+struct Args {
+    /// Board size, either <size> or <width>x<height>, each side from 3 to 10
+    #[arg(long, value_parser = parse_board_size)]
+    size: Option<(u8, u8)>,
+
+    /// How thoroughly the board is scrambled
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+
+    /// Seed for the first scramble, the same seed always gives the same board
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Color theme, overrides the theme and colors from the config file: dark, light, solarized,
+    /// high_contrast or the name of a theme file
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Start in fullscreen
+    #[arg(long)]
+    fullscreen: bool,
+
+    /// Start from a specific position, rows separated by '/' and the empty cell written as '_',
+    /// e.g. "1 2 3 / 4 5 6 / 7 _ 8"
+    #[arg(long, value_parser = parse_scramble, conflicts_with_all = ["size", "seed"])]
+    scramble: Option<Board>,
+
+    /// Frame rate cap
+    #[arg(long)]
+    fps: Option<u32>,
+
+    /// Sound volume, from 0 (muted) to 1
+    #[arg(long)]
+    volume: Option<f32>,
+
+    /// Continue a saved game
+    #[arg(long, conflicts_with = "scramble")]
+    load: Option<PathBuf>,
+
+    /// Play today's daily puzzle, the same 4x4 board for everyone
+    #[arg(long, conflicts_with_all = ["size", "difficulty", "seed", "scramble", "load"])]
+    daily: bool,
+
+    /// Print a shortest solution of the starting board in move notation and exit
+    #[arg(long)]
+    solve: bool,
+
+    /// Play back moves in move notation on the starting board, e.g. "U2 L D3"
+    #[arg(long)]
+    replay: Option<String>,
+
+    /// Play a level from a file, a board with walls drawn as rows of '.' for cells and '#' for walls
+    #[arg(long, conflicts_with_all = ["size", "scramble", "load", "daily", "watch_replay"])]
+    level: Option<PathBuf>,
+
+    /// Watch a replay saved after a solve
+    #[arg(long, conflicts_with_all = ["load", "scramble", "daily", "replay"])]
+    watch_replay: Option<PathBuf>,
+
+    /// Playback speed of replays, e.g. 2 for twice as fast
+    #[arg(long, default_value_t = 1.0)]
+    replay_speed: f32,
+
+    /// Play an image puzzle, the tiles show parts of the picture (a PNG or JPEG file) instead of numbers
+    #[arg(long)]
+    image: Option<PathBuf>,
+
+    /// Race the solver, it plays the same scramble on a board next to yours
+    #[arg(long)]
+    race: bool,
+
+    /// Host an online race on the port, the other player joins it with --join
+    #[arg(long, conflicts_with_all = ["scramble", "load", "daily", "level", "watch_replay", "race"])]
+    host: Option<u16>,
+
+    /// Join an online race hosted at the address, e.g. 192.168.1.20:7878
+    #[arg(long, conflicts_with_all = ["host", "size", "difficulty", "seed", "scramble", "load", "daily", "level"])]
+    join: Option<String>,
+
+    /// Watch an online race hosted at the address, both players' boards are shown as they play
+    #[arg(long, conflicts_with_all = ["host", "join", "size", "difficulty", "seed", "scramble", "load", "level"])]
+    spectate: Option<String>,
+
+    /// Let the chat of a twitch channel play the board, viewers vote on every move with !up, !down,
+    /// !left and !right
+    #[arg(long, conflicts_with_all = ["host", "join", "spectate", "watch_replay"])]
+    twitch: Option<String>,
+
+    /// Write every recorded solve to a CSV file and exit
+    #[arg(long)]
+    export_stats: Option<PathBuf>,
+}
+
+fn parse_scramble(value: &str) -> Result<Board, String> {
+    let board: Board = value.parse()?;
+
+    if !board.is_solvable() {
+        return Err("the position can not be solved".to_owned());
+    }
+
+    Ok(board)
+}
+
+// a check mark for a tile in its solved position or a cross for a misplaced one, in the top right
+// corner of the tile so it doesn't depend on telling colors apart
+fn draw_correctness_symbol(d: &mut impl Renderer, x: i32, y: i32, cell_size: i32, is_correct: bool, color: Color) {
+    let size = (cell_size / 6) as f32;
+    let (left, top) = ((x + cell_size) as f32 - size - 6.0, y as f32 + 6.0);
+    let thickness = (size / 5.0).max(2.0);
+
+    if is_correct {
+        let corner = (left + size * 0.4, top + size);
+        d.draw_segment((left, top + size * 0.55), corner, thickness, color);
+        d.draw_segment(corner, (left + size, top), thickness, color);
+    } else {
+        d.draw_segment((left, top), (left + size, top + size), thickness, color);
+        d.draw_segment((left + size, top), (left, top + size), thickness, color);
+    }
+}
+
+fn draw_message_window(d: &mut impl Renderer, text: &str, config: &Config) {
+    let (screen_width, screen_height) = d.screen_size();
+    let message_window = message_window_bounds(screen_width, screen_height);
+    d.draw_panel(message_window, config.colors.background_lighter);
+    d.draw_outline(message_window, 2.0, config.colors.border);
+
+    d.draw_text(
+        text,
+        message_window.x as i32 + 10,
+        message_window.y as i32 + 10,
+        28,
+        config.colors.text,
+    );
+}
+
+// the bar of a volume slider in the audio settings, which can also be clicked
+fn audio_slider_bounds(screen_width: i32, screen_height: i32, index: usize) -> Rectangle {
+    let window = message_window_bounds(screen_width, screen_height);
+    Rectangle::new(window.x + 20.0, window.y + 70.0 + index as f32 * 50.0, window.width - 40.0, 12.0)
+}
+
+fn draw_audio_settings(
+    d: &mut impl Renderer,
+    volumes: &audio::Volumes,
+    selected: usize,
+    muted: bool,
+    config: &Config,
+) {
+    let colors = &config.colors;
+    let (screen_width, screen_height) = d.screen_size();
+    let settings_window = message_window_bounds(screen_width, screen_height);
+    d.draw_panel(settings_window, colors.background_lighter);
+    d.draw_outline(settings_window, 2.0, colors.border);
+
+    let x = settings_window.x as i32 + 10;
+    let y = settings_window.y as i32 + 10;
+    let title = if muted { "Audio (muted)" } else { "Audio" };
+    d.draw_text(title, x, y, 28, colors.text);
+
+    let mut volumes = *volumes;
+
+    for (i, name) in audio::Volumes::NAMES.iter().enumerate() {
+        let volume = *volumes.get_mut(i);
+        let bar = audio_slider_bounds(screen_width, screen_height, i);
+        let marker = if i == selected { "> " } else { "  " };
+        let label = marker.to_owned() + name + ": " + &((volume * 100.0).round() as i32).to_string() + "%";
+
+        d.draw_text(&label, x, bar.y as i32 - 22, 20, colors.text);
+        d.draw_panel(bar, colors.background);
+        d.draw_panel(Rectangle::new(bar.x, bar.y, bar.width * volume, bar.height), colors.border);
+        d.draw_outline(bar, 1.0, colors.border);
+    }
+
+    d.draw_text(
+        &("[".to_owned()
+            + config.keybinds.key_name(Action::Mute)
+            + "] mute  ["
+            + config.keybinds.key_name(Action::AudioSettings)
+            + "] close"),
+        x,
+        y + 200,
+        20,
+        colors.text,
+    );
+}
+
+fn save_volumes(volumes: &audio::Volumes) {
+    save_setting(|config| volumes.save_to(config));
+}
+
+// writes a change made in game to the config file, the game keeps playing if it can't be saved
+fn save_setting(change: impl FnOnce(&mut Config)) {
+    if let Err(error) = config::update(change) {
+        eprintln!("could not save config: {}", error);
+    }
+}
+
+fn switch_theme(config: &mut Config, theme: &themes::Theme) {
+    config.theme = Some(theme.id.clone());
+    config.colors = theme.colors.clone();
+    save_setting(|config| config.theme = Some(theme.id.clone()));
+}
+
+// grows or shrinks both sides of the board by the step, so rectangular boards keep their shape
+fn step_board_size(width: u8, height: u8, step: i32) -> (u8, u8) {
+    let (shortest, longest) = (width.min(height) as i32, width.max(height) as i32);
+    let step = step.clamp(MIN_BOARD_SIZE as i32 - shortest, MAX_BOARD_SIZE as i32 - longest);
+    ((width as i32 + step) as u8, (height as i32 + step) as u8)
+}
+
+// a title, a summary line and a table of rows over the whole window, used by the leaderboards and
+// the statistics
+fn draw_table_screen(
+    d: &mut impl Renderer,
+    title: &str,
+    summary: &str,
+    columns: &[(&str, i32)],
+    rows: &[Vec<String>],
+    footer: &str,
+    config: &Config,
+) -> i32 {
+    let colors = &config.colors;
+    let (screen_width, screen_height) = d.screen_size();
+    d.draw_panel((0, 0, screen_width, screen_height), colors.background_lighter);
+    d.draw_text(title, 10, 10, 28, colors.text);
+    d.draw_text(summary, 10, 46, 20, colors.text);
+
+    // the summary can take up several lines
+    let header_y = 56 + summary.lines().count() as i32 * 24;
+
+    for (header, x) in columns {
+        d.draw_text(header, *x, header_y, 18, colors.text);
+    }
+
+    for (i, row) in rows.iter().enumerate() {
+        for (text, (_, x)) in row.iter().zip(columns) {
+            d.draw_text(text, *x, header_y + 26 + i as i32 * 24, 18, colors.text);
+        }
+    }
+
+    d.draw_text(footer, 10, screen_height - 30, 20, colors.text);
+
+    // where the table ends
+    header_y + 26 + rows.len() as i32 * 24
+}
+
+// the fastest solves of one board size
+fn draw_leaderboard(d: &mut impl Renderer, leaderboard: &stats::Leaderboard, config: &Config) {
+    let title = "Leaderboard ".to_owned() + &leaderboard.width.to_string() + "x" + &leaderboard.height.to_string();
+    let summary = match (leaderboard.fastest.first(), leaderboard.fewest_moves) {
+        (Some(fastest), Some(fewest_moves)) => {
+            "Best time: ".to_owned()
+                + &format_duration(Duration::from_millis(fastest.time_ms))
+                + "  Fewest moves: "
+                + &fewest_moves.to_string()
+        }
+        _ => "No solves of this size yet".to_owned(),
+    };
+    let rows: Vec<Vec<String>> = leaderboard
+        .fastest
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            vec![
+                (i + 1).to_string(),
+                daily::format_date(record.day()),
+                format_duration(Duration::from_millis(record.time_ms)),
+                record.moves.to_string(),
+                record.seed.map_or("-".to_owned(), |seed| seed.to_string()),
+            ]
+        })
+        .collect();
+
+    draw_table_screen(
+        d,
+        &title,
+        &summary,
+        &[("#", 10), ("Date", 45), ("Time", 170), ("Moves", 280), ("Seed", 350)],
+        &rows,
+        &("Press [".to_owned() + config.keybinds.key_name(Action::Leaderboard) + "] to continue"),
+        config,
+    );
+}
+
+// the global top times from the online leaderboard server
+fn draw_online_leaderboard(d: &mut impl Renderer, board: &Board, top_times: &mut online::TopTimes, config: &Config) {
+    let title = "Online ".to_owned() + &board.width.to_string() + "x" + &board.height.to_string();
+    let (summary, rows) = match top_times.poll() {
+        None => ("Loading...", Vec::new()),
+        Some(None) => ("Could not reach the leaderboard server", Vec::new()),
+        Some(Some(entries)) => {
+            let rows = entries
+                .iter()
+                .take(stats::LEADERBOARD_SIZE)
+                .enumerate()
+                .map(|(i, entry)| {
+                    vec![
+                        (i + 1).to_string(),
+                        format_duration(Duration::from_millis(entry.time_ms)),
+                        entry.moves.to_string(),
+                        entry.seed.map_or("-".to_owned(), |seed| seed.to_string()),
+                    ]
+                })
+                .collect();
+            ("Fastest solves of all players", rows)
+        }
+    };
+
+    draw_table_screen(
+        d,
+        &title,
+        summary,
+        &[("#", 10), ("Time", 45), ("Moves", 155), ("Seed", 225)],
+        &rows,
+        &("Press [".to_owned() + config.keybinds.key_name(Action::Leaderboard) + "] to continue"),
+        config,
+    );
+}
+
+// totals, the averages of the board size being played, the solve times of every board size and a
+// graph of the recent times of the selected size
+fn draw_statistics(
+    d: &mut RaylibDrawHandle,
+    board: &Board,
+    statistics: &stats::Statistics,
+    selected_size: usize,
+    best_streak: u32,
+    config: &Config,
+) {
+    let mut summary = statistics.solves.to_string()
+        + " solves, "
+        + &statistics.moves.to_string()
+        + " moves in "
+        + &format_duration(statistics.time_played);
+
+    if best_streak > 0 {
+        summary += &(", best streak ".to_owned() + &best_streak.to_string());
+    }
+
+    let size = statistics
+        .sizes
+        .iter()
+        .find(|size| size.width == board.width && size.height == board.height);
+
+    if let Some(size) = size {
+        summary += &("\n".to_owned()
+            + &size.width.to_string()
+            + "x"
+            + &size.height.to_string()
+            + "  ao5: "
+            + &size.ao5.map_or("-".to_owned(), format_duration)
+            + "  ao12: "
+            + &size.ao12.map_or("-".to_owned(), format_duration));
+    }
+
+    let rows: Vec<Vec<String>> = statistics
+        .sizes
+        .iter()
+        .map(|size| {
+            vec![
+                size.width.to_string() + "x" + &size.height.to_string(),
+                size.solves.to_string(),
+                format_duration(size.mean),
+                format_duration(size.median),
+                format_duration(size.best),
+                format_duration(size.worst),
+            ]
+        })
+        .collect();
+
+    let table_end = draw_table_screen(
+        d,
+        "Statistics",
+        &summary,
+        &[("Size", 10), ("#", 65), ("Mean", 105), ("Median", 200), ("Best", 295), ("Worst", 390)],
+        &rows,
+        &("[".to_owned()
+            + config.keybinds.key_name(Action::ExportStatistics)
+            + "] export to CSV  ["
+            + config.keybinds.key_name(Action::Statistics)
+            + "] close"),
+        config,
+    );
+
+    let graph_bounds = Rectangle::new(
+        10.0,
+        (table_end + 20) as f32,
+        (d.get_screen_width() - 20) as f32,
+        (d.get_screen_height() - table_end - 70) as f32,
+    );
+
+    // the graph is left out if the table leaves no room for it
+    if let Some(size) = statistics.sizes.get(selected_size).filter(|_| graph_bounds.height >= 80.0) {
+        draw_trend_graph(d, size, graph_bounds, config);
+    }
+}
+
+// the times of the last solves of one board size, oldest on the left
+fn draw_trend_graph(d: &mut RaylibDrawHandle, size: &stats::SizeStatistics, bounds: Rectangle, config: &Config) {
+    let colors = &config.colors;
+    let times = &size.times[size.times.len().saturating_sub(stats::GRAPH_SOLVES)..];
+    let title = "< ".to_owned()
+        + &size.width.to_string()
+        + "x"
+        + &size.height.to_string()
+        + " >  last "
+        + &times.len().to_string()
+        + " solve(s)";
+    font::draw_text(d, &title, bounds.x as i32, bounds.y as i32, 20, colors.text);
+
+    let plot = Rectangle::new(bounds.x, bounds.y + 28.0, bounds.width, bounds.height - 28.0);
+    d.draw_rectangle_lines_ex(plot, 1.0, colors.border);
+
+    let slowest = times.iter().copied().max().unwrap_or(0).max(1);
+    font::draw_text(
+        d,
+        &format_duration(Duration::from_millis(slowest)),
+        plot.x as i32 + 4,
+        plot.y as i32 + 4,
+        10,
+        colors.text,
+    );
+
+    // the points keep some distance from the border
+    let inner = Rectangle::new(plot.x + 8.0, plot.y + 20.0, plot.width - 16.0, plot.height - 28.0);
+    let points: Vec<Vector2> = times
+        .iter()
+        .enumerate()
+        .map(|(i, time)| {
+            let x = match times.len() {
+                1 => inner.x + inner.width / 2.0,
+                count => inner.x + inner.width * i as f32 / (count - 1) as f32,
+            };
+            Vector2::new(x, inner.y + inner.height * (1.0 - *time as f32 / slowest as f32))
+        })
+        .collect();
+
+    for pair in points.windows(2) {
+        d.draw_line_v(pair[0], pair[1], colors.text.fade(0.5));
+    }
+
+    for point in points {
+        d.draw_circle_v(point, 3.0, colors.text);
+    }
+}
+
+// shown above the win window when a solve beat the best time or move count of its board size
+fn draw_personal_best_banner(d: &mut impl Renderer, record: &stats::SolveRecord, config: &Config) {
+    let colors = &config.colors;
+    let (screen_width, _) = d.screen_size();
+    let mut lines = Vec::new();
+
+    if let Some(previous_best) = record.previous_best_time_ms {
+        lines.push(
+            "Time ".to_owned()
+                + &format_duration(Duration::from_millis(record.time_ms))
+                + ", was "
+                + &format_duration(Duration::from_millis(previous_best)),
+        );
+    }
+
+    if let Some(previous_best) = record.previous_best_moves {
+        lines.push("Moves ".to_owned() + &record.moves.to_string() + ", was " + &previous_best.to_string());
+    }
+
+    let banner = Rectangle::new(0.0, 20.0, screen_width as f32, 44.0 + lines.len() as f32 * 24.0);
+    d.draw_panel(banner, colors.background_lighter);
+    d.draw_outline(banner, 2.0, colors.border);
+    d.draw_text("New PB!", (screen_width - d.measure_text("New PB!", 32)) / 2, 26, 32, colors.text);
+
+    for (i, line) in lines.iter().enumerate() {
+        d.draw_text(line, (screen_width - d.measure_text(line, 20)) / 2, 62 + i as i32 * 24, 20, colors.text);
+    }
+}
+
+// the moves it took to reach the target against the fewest possible, while the solver is still
+// looking for them it says so
+fn draw_target_reached(d: &mut impl Renderer, game: &Game, optimal: Option<(u32, bool)>, config: &Config) {
+    let fewest = match optimal {
+        Some((moves, estimate)) => (if estimate { "~" } else { "" }).to_owned() + &moves.to_string(),
+        None => "...".to_owned(),
+    };
+
+    draw_message_window(
+        d,
+        &("Target reached!\nMoves: ".to_owned()
+            + &game.move_count.to_string()
+            + "\nFewest possible: "
+            + &fewest
+            + "\nPress ["
+            + config.keybinds.key_name(Action::Continue)
+            + "] to continue"),
+        config,
+    );
+}
+
+// who won a race against another player, and the time and moves of both
+fn draw_race_result(
+    d: &mut impl Renderer,
+    title: &str,
+    names: [&str; 2],
+    results: [(Duration, u32); 2],
+    continue_key: bool,
+    config: &Config,
+) {
+    let mut text = title.to_owned();
+
+    for (name, (time, moves)) in names.iter().zip(results) {
+        text += &("\n".to_owned() + name + ": " + &format_duration(time) + ", " + &moves.to_string() + " moves");
+    }
+
+    // the guest of an online race waits for the host to start the next one
+    if continue_key {
+        text += &("\nPress [".to_owned() + config.keybinds.key_name(Action::Continue) + "] to play again");
+    }
+
+    draw_message_window(d, &text, config);
+}
+
+// the message once the board is solved, daily is the streak if it was the daily puzzle
+fn draw_solved(
+    d: &mut impl Renderer,
+    game: &Game,
+    daily: Option<u32>,
+    solve_record: Option<&stats::SolveRecord>,
+    race: Option<&race::Race>,
+    config: &Config,
+) {
+    let keybinds = &config.keybinds;
+
+    if game.playback.is_some() {
+        draw_message_window(
+            d,
+            &("Replay finished\nTime: ".to_owned()
+                + &format_duration(game.timer.elapsed())
+                + "\nPress ["
+                + keybinds.key_name(Action::WatchReplay)
+                + "] to watch again\nPress ["
+                + keybinds.key_name(Action::Continue)
+                + "] to continue"),
+            config,
+        );
+        return;
+    }
+
+    if game.auto_solved {
+        draw_message_window(
+            d,
+            &("Solved by the computer\nPress [".to_owned()
+                + keybinds.key_name(Action::Continue)
+                + "] to continue"),
+            config,
+        );
+        return;
+    }
+
+    if let Some(marathon) = game.marathon.as_ref().filter(|marathon| marathon.is_finished()) {
+        draw_marathon_summary(d, marathon, config);
+        return;
+    }
+
+    if let Some(streak) = daily {
+        draw_message_window(
+            d,
+            &("Daily puzzle solved!\nTime: ".to_owned()
+                + &format_duration(game.timer.elapsed())
+                + "\nStreak: "
+                + &streak.to_string()
+                + " day(s)\nPress ["
+                + keybinds.key_name(Action::Share)
+                + "] to copy a summary\nPress ["
+                + keybinds.key_name(Action::Continue)
+                + "] to continue"),
+            config,
+        );
+    } else {
+        let efficiency = solve_record
+            .and_then(|record| record.format_efficiency())
+            .unwrap_or("...".to_owned());
+
+        let title = match race {
+            Some(race) if race.is_beaten_by(game.timer.elapsed()) => "You beat the computer!",
+            Some(_) => "The computer was faster!",
+            None => "You win!",
+        };
+
+        draw_message_window(
+            d,
+            &(title.to_owned()
+                + "\nTime: "
+                + &format_duration(game.timer.elapsed())
+                + "\nUndos: "
+                + &game.undo_count.to_string()
+                + "\nEfficiency: "
+                + &efficiency
+                + "\nPress ["
+                + keybinds.key_name(Action::Share)
+                + "] to copy a summary\nPress ["
+                + keybinds.key_name(Action::Continue)
+                + "] to continue"),
+            config,
+        );
+    }
+
+    if let Some(record) = solve_record.filter(|record| record.is_personal_best()) {
+        draw_personal_best_banner(d, record, config);
+    }
+}
+
+// the time of every board of the finished marathon, in a smaller font than the other messages so
+// they all fit
+fn draw_marathon_summary(d: &mut impl Renderer, marathon: &Marathon, config: &Config) {
+    let colors = &config.colors;
+    let (screen_width, screen_height) = d.screen_size();
+    let message_window = message_window_bounds(screen_width, screen_height);
+    d.draw_panel(message_window, colors.background_lighter);
+    d.draw_outline(message_window, 2.0, colors.border);
+
+    let (x, mut y) = (message_window.x as i32 + 10, message_window.y as i32 + 10);
+    d.draw_text("Marathon finished!", x, y, 28, colors.text);
+    y += 36;
+
+    for (size, time, moves) in &marathon.solves {
+        let line = format!("{}x{}  {}  {} moves", size, size, format_duration(*time), moves);
+        d.draw_text(&line, x, y, 20, colors.text);
+        y += 22;
+    }
+
+    let moves: u32 = marathon.solves.iter().map(|(_, _, moves)| moves).sum();
+    let total = format!("Total  {}  {} moves", format_duration(marathon.total_time()), moves);
+    d.draw_text(&total, x, y + 4, 20, colors.text);
+
+    let footer = "Press [".to_owned() + config.keybinds.key_name(Action::Continue) + "] to start again";
+    d.draw_text(&footer, x, message_window.y as i32 + message_window.height as i32 - 30, 20, colors.text);
+}
+
+fn copy_to_clipboard(rl: &mut RaylibHandle, text: &str) {
+    if let Err(error) = rl.set_clipboard_text(text) {
+        eprintln!("could not copy to the clipboard: {}", error);
+    }
+}
+
+// along the bottom of the board, just above the hud
+fn draw_toast(d: &mut impl Renderer, text: &str, config: &Config) {
+    let text_width = d.measure_text(text, 20);
+    let (screen_width, screen_height) = d.screen_size();
+    let x = (screen_width - text_width) / 2;
+    let y = screen_height - config.window.hud_height - 40;
+
+    d.draw_panel((x - 10, y, text_width + 20, 30), config.colors.background.fade(0.9));
+    d.draw_text(text, x, y + 6, 20, config.colors.text);
+}
+
+// the seconds of inspection left along the top of the window, the board stays visible below it
+fn draw_inspection(d: &mut impl Renderer, left: Duration, config: &Config) {
+    let seconds_left = left.as_secs_f32().ceil() as u64;
+    let text = "Inspection: ".to_owned()
+        + &seconds_left.to_string()
+        + "s  ["
+        + config.keybinds.key_name(Action::Continue)
+        + "] skip";
+    let (screen_width, _) = d.screen_size();
+
+    d.draw_panel((0, 0, screen_width, 30), config.colors.background.fade(0.9));
+    d.draw_text(&text, (screen_width - d.measure_text(&text, 20)) / 2, 6, 20, config.colors.text);
+}
+
+// the stackmat's light along the top of the window: off until the key is held, red while it is held
+// too short to start and green once letting go starts the timer
+fn draw_stackmat(d: &mut RaylibDrawHandle, held: bool, ready: bool, config: &Config) {
+    let key = config.keybinds.key_name(Action::Continue);
+    let (light, text) = match (held, ready) {
+        (_, true) => (Some(Color::GREEN), "Let go to start".to_owned()),
+        (true, false) => (Some(Color::RED), "Hold...".to_owned()),
+        (false, false) => (None, "Hold [".to_owned() + key + "] to start"),
+    };
+    let screen_width = d.get_screen_width();
+    let text_x = (screen_width - font::measure_text(&text, 20)) / 2;
+
+    d.draw_rectangle(0, 0, screen_width, 30, config.colors.background.fade(0.9));
+    font::draw_text(d, &text, text_x, 6, 20, config.colors.text);
+
+    match light {
+        Some(color) => d.draw_circle(text_x - 16, 15, 8.0, color),
+        None => d.draw_circle_lines(text_x - 16, 15, 8.0, config.colors.text),
+    }
+}
+
+// the splits of the solve so far in the top right corner of the board, each with how far it is
+// ahead of or behind the best split of its phase. the phases still to come show their best split
+fn draw_splits(d: &mut impl Renderer, game: &Game, best: &[u64], area: Rectangle, config: &Config) {
+    let colors = &config.colors;
+    let phases = splits::phases(game.board.width, game.board.height);
+
+    let lines: Vec<String> = (phases.iter().enumerate())
+        .map(|(index, phase)| {
+            let best = best.get(index).map(|best| Duration::from_millis(*best));
+
+            match (game.splits.get(index), best) {
+                (Some(split), Some(best)) => {
+                    phase.name.clone() + "  " + &format_duration(*split) + "  " + &splits::format_delta(*split, best)
+                }
+                (Some(split), None) => phase.name.clone() + "  " + &format_duration(*split),
+                (None, Some(best)) => phase.name.clone() + "  (" + &format_duration(best) + ")",
+                (None, None) => phase.name.clone() + "  --",
+            }
+        })
+        .collect();
+
+    let width = lines.iter().map(|line| d.measure_text(line, 14)).max().unwrap_or(0) + 20;
+    let height = lines.len() as i32 * 18 + 12;
+    let x = (area.x + area.width) as i32 - width - 10;
+    d.draw_panel((x, 10, width, height), colors.background.fade(0.9));
+
+    for (index, line) in lines.iter().enumerate() {
+        d.draw_text(line, x + 10, 16 + index as i32 * 18, 14, colors.text);
+    }
+}
+
+// the votes of the round so far along the top of the window, or how connecting to the chat went
+fn draw_chat_votes(d: &mut impl Renderer, chat: &twitch::ChatPlays, config: &Config) {
+    let channel = "#".to_owned() + &chat.channel;
+
+    let text = match (chat.has_joined(), chat.is_closed()) {
+        (false, false) => "Connecting to ".to_owned() + &channel + "...",
+        (false, true) => "Could not connect to ".to_owned() + &channel,
+        (true, true) => "Lost the connection to ".to_owned() + &channel,
+        (true, false) => {
+            let tallies: Vec<String> = (chat.tallies().iter().zip(twitch::VOTES))
+                .map(|((_, votes), (vote, _))| vote.to_owned() + " " + &votes.to_string())
+                .collect();
+            let seconds_left = chat.time_left().as_secs_f32().ceil() as u64;
+            tallies.join("  ") + "  next move in " + &seconds_left.to_string() + "s"
+        }
+    };
+
+    let (screen_width, _) = d.screen_size();
+    d.draw_panel((0, 0, screen_width, 30), config.colors.background.fade(0.9));
+    d.draw_text(&text, 10, 6, 20, config.colors.text);
+}
+
+// two bars along the bottom of the hud, the ghost's progress above the player's
+fn draw_ghost_race(d: &mut impl Renderer, game: &Game, ghost: &replay::Ghost, config: &Config) {
+    let colors = &config.colors;
+    let (screen_width, screen_height) = d.screen_size();
+    let y = screen_height - 8;
+    let width = screen_width as f32;
+    let ghost_progress = ghost.progress_at(game.timer.elapsed()).clamp(0.0, 1.0);
+    let player_progress = ghost.progress_of(&game.board).clamp(0.0, 1.0);
+
+    d.draw_panel((0, y, (width * ghost_progress) as i32, 3), colors.text.fade(0.4));
+    d.draw_panel((0, y + 4, (width * player_progress) as i32, 3), colors.text);
+}
+
+// the solver's board in its half of the window, with a note while it has no moves to play
+fn draw_race<R: Renderer>(
+    d: &mut R,
+    area: Rectangle,
+    race: &race::Race,
+    animation: &TileAnimation,
+    picture: Option<&R::Picture>,
+    config: &Config,
+) {
+    let offset_x = area.x as i32;
+    draw_board(d, &race.board, Layout::new(&race.board, area), animation, picture, Visibility::All, config);
+    d.draw_segment((area.x, 0.0), (area.x, area.height), 1.0, config.colors.border);
+
+    let status = match (race.is_thinking(), race.finish_time()) {
+        (true, _) => Some("Thinking..."),
+        (false, None) => Some("The computer gave up"),
+        (false, Some(_)) => None,
+    };
+
+    if let Some(status) = status {
+        d.draw_text(status, offset_x + 10, 10, 20, config.colors.text);
+    }
+}
+
+// the other player's board next to this one's, with a line above each board for whose it is. the
+// hud along the bottom is this player's
+fn draw_opponent<R: Renderer>(
+    d: &mut R,
+    area: Rectangle,
+    board: &Board,
+    labels: [&str; 2],
+    animation: &TileAnimation,
+    picture: Option<&R::Picture>,
+    config: &Config,
+) {
+    let colors = &config.colors;
+    let offset_x = area.x as i32;
+    draw_board(d, board, Layout::new(board, area), animation, picture, Visibility::All, config);
+    d.draw_segment((area.x, 0.0), (area.x, area.height), 1.0, colors.border);
+
+    d.draw_panel((0, 0, offset_x * 2, 30), colors.background.fade(0.9));
+    d.draw_text(labels[0], 10, 6, 20, colors.text);
+    d.draw_text(labels[1], offset_x + 10, 6, 20, colors.text);
+}
+
+// the position to reach in the target mode, small in the bottom right corner of the board
+fn draw_target(d: &mut impl Renderer, board: &Board, layout: Layout, config: &Config) {
+    let target = match &board.target {
+        Some(target) => target,
+        None => return,
+    };
+
+    let colors = &config.colors;
+    let cell_size = layout.cell_size / 3;
+    let font_size = (cell_size * 2 / 5).max(10);
+    let (width, height) = (cell_size * board.width as i32, cell_size * board.height as i32);
+    let x = layout.x + layout.cell_size * board.width as i32 - width - 10;
+    let y = layout.y + layout.cell_size * board.height as i32 - height - 10;
+
+    d.draw_panel((x, y, width, height), colors.background_darker);
+
+    for (i, cell) in target.iter().enumerate().filter(|(_, cell)| !board.is_empty_value(**cell)) {
+        let cell_x = x + (i % board.width as usize) as i32 * cell_size;
+        let cell_y = y + (i / board.width as usize) as i32 * cell_size;
+        d.draw_panel((cell_x, cell_y, cell_size, cell_size), colors.background);
+        d.draw_outline((cell_x, cell_y, cell_size, cell_size), 1.0, colors.border);
+
+        let number = board.tile_label(*cell);
+        let text_x = cell_x + (cell_size - d.measure_text(&number, font_size)) / 2;
+        d.draw_text(&number, text_x, cell_y + (cell_size - font_size) / 2, font_size, colors.text);
+    }
+
+    d.draw_outline(Rectangle::new(x as f32, y as f32, width as f32, height as f32), 2.0, colors.border);
+}
+
+// a small picture of the solved board in the bottom right corner of the board, for image puzzles
+fn draw_thumbnail(d: &mut RaylibDrawHandle, board: &Board, picture: &Picture, layout: Layout, config: &Config) {
+    let (board_width, board_height) = (
+        (layout.cell_size * board.width as i32) as f32,
+        (layout.cell_size * board.height as i32) as f32,
+    );
+    let (width, height) = (board_width / 4.0, board_height / 4.0);
+    let bounds = Rectangle::new(
+        layout.x as f32 + board_width - width - 10.0,
+        layout.y as f32 + board_height - height - 10.0,
+        width,
+        height,
+    );
+
+    picture.draw(d, board.width, board.height, bounds, Color::WHITE);
+    d.draw_rectangle_lines_ex(bounds, 2.0, config.colors.border);
+}
+
+// lightens the tiles a click would slide, the one under the mouse and those between it and the
+// empty cell, so it can be seen which tiles can be clicked
+fn draw_hover(d: &mut RaylibDrawHandle, board: &Board, layout: Layout, config: &Config) {
+    let index = match layout.get_index_at(board, d.get_mouse_position()) {
+        Some(index) => index,
+        None => return,
+    };
+
+    let direction = match board.get_direction_to(index) {
+        Some(direction) => direction,
+        None => return,
+    };
+
+    let cell_size = layout.cell_size as f32;
+    let mut cell = board.get_empty_index();
+
+    while cell != index {
+        cell = match board.get_neighbor_index(cell, direction) {
+            Some(next) => next,
+            None => break,
+        };
+
+        let (x, y) = layout.cell_position(cell, board.width);
+        d.draw_rectangle_rec(Rectangle::new(x as f32, y as f32, cell_size, cell_size), config.colors.text.fade(0.12));
+    }
+}
+
+// outlines the tile that should be moved next. it pulses, to stand out from the borders of the tiles
+fn draw_hint(d: &mut RaylibDrawHandle, board: &Board, index: usize, layout: Layout, config: &Config) {
+    let cell_size = layout.cell_size;
+    let (x, y) = layout.cell_position(index, board.width);
+    let pulse = ((d.get_time() * 6.0).sin() * 0.5 + 0.5) as f32;
+
+    d.draw_rectangle_lines_ex(
+        Rectangle::new(x as f32, y as f32, cell_size as f32, cell_size as f32),
+        4.0,
+        config.colors.text.fade(0.4 + 0.6 * pulse),
+    );
+}
+
+// daily is the day of the daily puzzle being played, if any
+// recent_times holds the solve times of the board size for the averages, once they are loaded
+fn draw_hud(
+    d: &mut impl Renderer,
+    game: &Game,
+    daily: Option<i64>,
+    recent_times: Option<&stats::RecentTimes>,
+    config: &Config,
+) {
+    let colors = &config.colors;
+    let (screen_width, screen_height) = d.screen_size();
+    let y = screen_height - config.window.hud_height;
+    d.draw_panel((0, y, screen_width, config.window.hud_height), colors.background);
+    d.draw_segment((0.0, y as f32), (screen_width as f32, y as f32), 1.0, colors.border);
+
+    let averages = [("ao5 ", 5), ("ao12 ", 12)]
+        .iter()
+        .filter_map(|(name, count)| {
+            let average = recent_times?.average_of(*count)?;
+            Some(name.to_string() + &format_duration(average))
+        })
+        .collect::<Vec<String>>()
+        .join("  ");
+
+    // zen mode keeps the hud empty but for its name
+    if game.zen {
+        d.draw_text("Zen", (screen_width - d.measure_text("Zen", 20)) / 2, y + 10, 20, colors.text);
+        return;
+    }
+
+    // the averages go in a small line below the rest, which moves up to make room for it
+    let text_y = if averages.is_empty() {
+        y + 10
+    } else {
+        d.draw_text(&averages, 10, y + 25, 10, colors.text);
+        y + 4
+    };
+
+    d.draw_text(&("Moves: ".to_owned() + &game.move_count.to_string()), 10, text_y, 20, colors.text);
+
+    let label = match (&game.playback, daily, game.seed) {
+        (Some(playback), _, _) => Some("Replay ".to_owned() + &playback.speed.to_string() + "x"),
+        (None, Some(day), _) => Some("Daily ".to_owned() + &daily::format_date(day)),
+        (None, None, _) if game.endgame => Some("Endgame trainer".to_owned()),
+        (None, None, _) if game.marathon.is_some() => game.marathon.as_ref().map(|marathon| {
+            let current = if game.board.solved { Duration::ZERO } else { game.timer.elapsed() };
+            "Marathon ".to_owned() + &format_duration(marathon.total_time() + current)
+        }),
+        (None, None, _) if game.time_attack.is_some() => game.time_attack.as_ref().map(|time_attack| {
+            let solved = "Solved: ".to_owned() + &time_attack.boards_solved.to_string();
+            solved + "  Left: " + &format_duration(time_attack.time_left())
+        }),
+        (None, None, _) if game.streak.is_some() => game.streak.map(|streak| {
+            let current = "Streak: ".to_owned() + &streak.current.to_string();
+            current + "  Best: " + &streak.best.to_string() + "  Under " + &format_duration(streak.target)
+        }),
+        (None, None, _) if game.fog => Some("Fog of war".to_owned()),
+        (None, None, _) if game.board.wrap => Some("Wraparound".to_owned()),
+        (None, None, _) if game.board.gravity => Some("Gravity".to_owned()),
+        (None, None, _) if game.target => Some("Reach the target".to_owned()),
+        (None, None, _) if !game.board.letters.is_empty() => Some(config.letter_words.join(" ")),
+        (None, None, _) if game.board.two_blanks => {
+            Some("Two blanks, [".to_owned() + config.keybinds.key_name(Action::SwitchBlank) + "] switch")
+        }
+        (None, None, _) if game.blindfold.is_some() => match game.memorize_time_left() {
+            Some(left) => Some("Memorize: ".to_owned() + &(left.as_secs() + 1).to_string() + "s"),
+            None => Some("Blindfold, [".to_owned() + config.keybinds.key_name(Action::Continue) + "] reveal"),
+        },
+        (None, None, Some(seed)) => Some("Seed: ".to_owned() + &seed.to_string()),
+        (None, None, None) => None,
+    };
+
+    if let Some(label) = label {
+        d.draw_text(&label, (screen_width - d.measure_text(&label, 20)) / 2, text_y, 20, colors.text);
+    }
+
+    let time = format_duration(game.timer.elapsed());
+    d.draw_text(&time, screen_width - d.measure_text(&time, 20) - 10, text_y, 20, colors.text);
+}
+
+// the parts of the window above the hud the player's board and the solver's board are fit into.
+// racing the solver or a second player splits it in half, otherwise the player's board gets all of it
+fn get_board_areas(screen_width: i32, screen_height: i32, race: bool, config: &Config) -> (Rectangle, Rectangle) {
+    let height = (screen_height - config.window.hud_height).max(0) as f32;
+    let width = screen_width as f32 / if race { 2.0 } else { 1.0 };
+
+    (Rectangle::new(0.0, 0.0, width, height), Rectangle::new(width, 0.0, width, height))
+}
+
+// the statistics of every solve, with the index of the board's size among them to show it first
+fn load_statistics(board: &Board) -> Option<(stats::Statistics, usize)> {
+    match stats::load() {
+        Ok(records) => {
+            let statistics = stats::Statistics::new(&records);
+            let size = statistics
+                .sizes
+                .iter()
+                .position(|size| size.width == board.width && size.height == board.height)
+                .unwrap_or(0);
+            Some((statistics, size))
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            None
+        }
+    }
+}
+
+// cells shrink on big boards so the window still fits on screen,
+// and grow on small boards so the window is never smaller than min_board_pixels
+fn get_cell_size(board: &Board, window: &WindowConfig) -> i32 {
+    let longest_side = board.width.max(board.height) as i32;
+
+    (window.max_board_pixels / longest_side)
+        .min(window.cell_size)
+        .max(window.min_board_pixels / longest_side)
+}
+
+fn get_window_size(board: &Board, config: &Config) -> (i32, i32) {
+    let cell_size = get_cell_size(board, &config.window);
+    let window_width = cell_size * board.width as i32;
+    let window_height = cell_size * board.height as i32 + config.window.hud_height;
+
+    // racing the solver or another player puts their board next to the player's
+    if config.solver_race || config.versus || config.online_race {
+        (window_width * 2, window_height)
+    } else {
+        (window_width, window_height)
+    }
+}
+
+// resizes the window to fit the board, e.g. after loading a game of a different size. in fullscreen
+// the window gets the size once fullscreen is left
+fn fit_window(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    board: &Board,
+    level_index: i32,
+    windowed_size: &mut Option<(i32, i32)>,
+    config: &Config,
+) {
+    let window_size = get_window_size(board, config);
+
+    match windowed_size {
+        Some(size) => *size = window_size,
+        None => rl.set_window_size(window_size.0, window_size.1),
+    }
+
+    rl.set_window_title(thread, &format_window_title(board, level_index));
+}
+
+// switches between the window and fullscreen. windowed_size is the size the window goes back to,
+// it is only set while in fullscreen
+fn toggle_fullscreen(rl: &mut RaylibHandle, windowed_size: &mut Option<(i32, i32)>, config: &Config) {
+    let monitor = rl.get_current_monitor();
+    let (monitor_width, monitor_height) = (get_monitor_width(monitor), get_monitor_height(monitor));
+    let undecorated = WindowState::default().set_window_undecorated(true);
+
+    match windowed_size.take() {
+        None => {
+            *windowed_size = Some((rl.get_screen_width(), rl.get_screen_height()));
+
+            if config.borderless_fullscreen {
+                rl.set_window_state(undecorated);
+                rl.set_window_position(0, 0);
+                rl.set_window_size(monitor_width, monitor_height);
+            } else {
+                // raylib keeps the size of the window in fullscreen, so it is made as big as the monitor first
+                rl.set_window_size(monitor_width, monitor_height);
+                rl.toggle_fullscreen();
+            }
+        }
+        Some((width, height)) => {
+            if rl.is_window_fullscreen() {
+                rl.toggle_fullscreen();
+            } else {
+                rl.clear_window_state(undecorated);
+            }
+
+            rl.set_window_size(width, height);
+            rl.set_window_position((monitor_width - width) / 2, (monitor_height - height) / 2);
+        }
+    }
+}
+
+// the way the empty cell moves for a direction pressed or swiped. with move_tiles set the direction
+// is the one the tile next to the empty cell slides in, which moves the empty cell the other way
+fn input_direction(direction: Direction, config: &Config) -> Direction {
+    if config.move_tiles {
+        direction.opposite()
+    } else {
+        direction
+    }
+}
+
+// the player moving tiles. the hint and auto-solve keys start the solver on the board, hinted is
+// whether the tile to move next was found for the board already
+fn update_playing(
+    rl: &mut RaylibHandle,
+    game: &mut Game,
+    solver_job: &mut Option<(solver::SolverJob, SolvePurpose)>,
+    input: &mut Input,
+    hinted: bool,
+    race: bool,
+    config: &Config,
+) {
+    let keybinds = &config.keybinds.with_second_player(config.versus);
+
+    if keybinds.is_pressed(rl, Action::AutoSolve) {
+        let job = solver::SolverJob::start(&game.board, config.solver_heuristic, AUTO_SOLVE_MAX_NODES);
+        *solver_job = Some((job, SolvePurpose::AutoSolve));
+    }
+
+    for direction in input.take_moves() {
+        game.events.publish(Event::MoveRequested(input_direction(direction, config)));
+    }
+
+    // tapping a tile slides it and the ones between it and the empty cell, a swipe that starts on
+    // the board moves the empty cell the way it went
+    if let Some(gesture) = input.touch.gesture() {
+        let (area, _) = get_board_areas(rl.get_screen_width(), rl.get_screen_height(), race, config);
+        let layout = Layout::new(&game.board, area);
+
+        match gesture {
+            TouchGesture::Tap(position) => {
+                if let Some(index) = layout.get_index_at(&game.board, position) {
+                    game.move_tile_at(index);
+                }
+            }
+            TouchGesture::Swipe(start, direction) => {
+                if layout.get_index_at(&game.board, start).is_some() {
+                    game.events.publish(Event::MoveRequested(input_direction(direction, config)));
+                }
+            }
+        }
+    }
+
+    // the hint is shown while the key is held, after a move the solver looks for the next one. the
+    // solver can't find moves for two blanks, wraparound or gravity, so there are no hints for them
+    let board = &game.board;
+    let has_hints = !board.two_blanks && !board.wrap && !board.gravity;
+
+    if keybinds.is_down(rl, Action::Hint) && has_hints && !hinted && !board.solved {
+        let job = solver::SolverJob::start(&game.board, config.solver_heuristic, HINT_MAX_NODES);
+        *solver_job = Some((job, SolvePurpose::Hint));
+    }
+
+    if keybinds.is_pressed(rl, Action::Undo) {
+        game.undo();
+    }
+
+    if keybinds.is_pressed(rl, Action::Redo) {
+        game.redo();
+    }
+
+    if keybinds.is_pressed(rl, Action::SwitchBlank) {
+        game.board.switch_blank();
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut config = config::load();
+
+    let mut themes = themes::Themes::load();
+
+    if let Some(theme) = args.theme {
+        config.theme = Some(theme);
+    }
+
+    if let Some(id) = &config.theme {
+        match themes.get(id) {
+            Some(theme) => config.colors = theme.colors.clone(),
+            None => eprintln!("unknown theme {}", id),
+        }
+    }
+
+    if let Some(fps) = args.fps {
+        config.fps = fps;
+    }
+
+    if let Some(volume) = args.volume {
+        config.volume = volume.clamp(0.0, 1.0);
+    }
+
+    if args.race {
+        config.solver_race = true;
+    }
+
+    if let Some(path) = &args.image {
+        config.image = Some(path.clone());
+    }
+
+    if let Some(path) = &args.export_stats {
+        match stats::export_csv(path) {
+            Ok(count) => println!("exported {} solve(s) to {}", count, path.display()),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    let online_race = match (args.host, &args.join, &args.spectate) {
+        (Some(port), _, _) => Some(multiplayer::OnlineRace::host(port)),
+        (None, Some(address), _) => Some(multiplayer::OnlineRace::join(address, false)),
+        (None, None, Some(address)) => Some(multiplayer::OnlineRace::join(address, true)),
+        (None, None, None) => None,
+    };
+    let mut online_race = online_race.transpose().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    });
+    config.online_race = online_race.is_some();
+
+    let vote_time = Duration::from_secs(config.twitch_vote_seconds);
+    let mut chat = args.twitch.as_ref().map(|channel| twitch::ChatPlays::connect(channel, vote_time));
+
+    let (mut width, mut height) = args.size.unwrap_or((config.width, config.height));
+    let mut completed_level_count: i32 = 0;
+    let mut difficulty = args.difficulty.unwrap_or(config.difficulty);
+    let mut settings: Option<settings::Settings> = None;
+    let mut game = Game::new(Board::solved(width, height));
+    let save_path = args.load.clone().unwrap_or(PathBuf::from(save::DEFAULT_SAVE_PATH));
+    let autosave_path = save::autosave_path();
+    let mut resume_state = None;
+    let mut daily_record = daily::load();
+    let mut streak_record = streak::load();
+    let mut best_splits = splits::load();
+    let inspection_time = Duration::from_secs(config.inspection_seconds);
+    let stackmat_hold_time = Duration::from_millis(config.stackmat_hold_ms);
+    let mut inspection =
+        inspection::Inspection::new(config.inspection, inspection_time, config.stackmat, stackmat_hold_time);
+    let mut daily_day = None;
+    // the hinted tile, together with the board it was computed for so it disappears after any move
+    let mut hint: Option<(Vec<i32>, usize)> = None;
+    let auto_solve_delay = Duration::from_millis(config.auto_solve_delay_ms);
+    let mut next_auto_solve_move = Instant::now();
+    let mut solver_job: Option<(solver::SolverJob, SolvePurpose)> = None;
+    let mut solve_recorder = stats::Recorder::new(config.solver_heuristic);
+    let mut replay_recorder = replay::Recorder::new(config.online_leaderboard_url.clone());
+    let mut target_score = TargetScore::new(config.solver_heuristic);
+    // the best previous solve of the current scramble, and the start position it was looked up for
+    let mut ghost: Option<replay::Ghost> = None;
+    let mut ghost_start: Option<Vec<i32>> = None;
+    // the solver's side of the race on the current scramble
+    let mut race: Option<race::Race> = None;
+    // the second player's side of a split-screen race
+    let mut versus: Option<versus::Versus> = None;
+    // the leaderboard of the current board size while it is open
+    let mut leaderboard: Option<stats::Leaderboard> = None;
+    let mut online_top_times: Option<online::TopTimes> = None;
+    // the solve times of the board size being played, for the averages in the hud
+    let mut recent_times: Option<stats::RecentTimes> = None;
+    let mut tile_animation = TileAnimation::new(config.animation_speed);
+    let mut race_animation = TileAnimation::new(config.animation_speed);
+    let mut celebration = confetti::Celebration::new(!config.reduced_motion);
+    let animate_scramble = config.animate_scramble && !config.reduced_motion;
+    let mut scrambles = animation::Scrambles::new(animate_scramble, config.animation_speed);
+    // a short message over everything else and when it was shown
+    let mut toast: Option<(String, Instant)> = None;
+    let mut volumes = audio::Volumes::new(&config);
+    let mut muted = config.muted;
+    // the selected slider while the audio settings are open
+    let mut audio_settings: Option<usize> = None;
+    let mut state = GameState::Playing;
+    let mut quit = false;
+
+    let level = args.level.as_ref().map(|path| {
+        level::load(path).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        })
+    });
+
+    match (&args.load, args.scramble, &args.watch_replay, level) {
+        (Some(path), _, _, _) => match save::load(path) {
+            Ok(state) => (game, completed_level_count) = state.restore(),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        },
+        (None, Some(board), _, _) => game = Game::new(board),
+        (None, None, Some(path), _) => match replay::load(path) {
+            Ok(replay) => game = Game::from_replay(replay, args.replay_speed),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        },
+        // a level is scrambled like any other board, the walls stay where they are
+        (None, None, None, Some(board)) => {
+            game = Game::new(board);
+            game.scramble(difficulty, args.seed);
+        }
+        (None, None, None, None) if args.daily => {
+            let day = daily::today();
+            game = Game::new(Board::solved(daily::WIDTH, daily::HEIGHT));
+            game.scramble(daily::DIFFICULTY, Some(daily::seed(day)));
+            daily_day = Some(day);
+        }
+        (None, None, None, None) => {
+            game.scramble(difficulty, args.seed);
+
+            // the game starts on the menu unless a specific board was asked for
+            if args.seed.is_none() && args.replay.is_none() && online_race.is_none() && args.twitch.is_none() {
+                let mode = if config.solver_race { menu::Mode::Race } else { menu::Mode::Classic };
+                state = GameState::Menu(menu::Menu::new(width, height, mode, Goal::RowMajor));
+            }
+
+            // only offer to resume if there is an autosave and it can actually be loaded, an online race
+            // plays the host's boards instead
+            resume_state = autosave_path
+                .as_ref()
+                .filter(|path| storage::exists(path) && online_race.is_none())
+                .and_then(|path| save::load(path).map_err(|error| eprintln!("{}", error)).ok());
+        }
+    }
+
+    if args.solve {
+        let solution = solver::solve(
+            &game.board,
+            config.solver_heuristic,
+            AUTO_SOLVE_MAX_NODES,
+            &AtomicBool::new(false),
+        );
+
+        match solution {
+            Some(moves) => println!("{}", notation::format(&moves)),
+            None => {
+                eprintln!("no solution found, the board is too far from solved");
+                std::process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    if let Some(replay) = &args.replay {
+        let moves = notation::parse(replay).and_then(|moves| {
+            let mut board = game.board.clone();
+
+            match moves.iter().position(|direction| !board.move_empty(*direction)) {
+                Some(index) => Err(format!("move {} of the replay leaves the board", index + 1)),
+                None => Ok(moves),
+            }
+        });
+
+        match moves {
+            Ok(moves) => game.start_auto_solve(moves),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (window_width, window_height) = get_window_size(&game.board, &config);
+    let mut builder = raylib::init();
+    builder
+        .size(window_width, window_height)
+        .resizable()
+        .title(&format_window_title(&game.board, completed_level_count));
+
+    // the builder has no option for it, but raylib adds the flags together, so it is set beforehand.
+    // raylib scales the window up by the monitor's scale factor then, and everything is drawn at the
+    // sizes it is meant to have on screen
+    if config.high_dpi {
+        unsafe {
+            raylib::ffi::SetConfigFlags(raylib::ffi::ConfigFlags::FLAG_WINDOW_HIGHDPI as u32);
+        }
+    }
+
+    let (mut rl, thread) = builder.build();
+    let mut windowed_size: Option<(i32, i32)> = None;
+
+    if args.fullscreen {
+        toggle_fullscreen(&mut rl, &mut windowed_size, &config);
+    }
+
+    rl.set_target_fps(config.fps);
+    // escape pauses the game, cancels the solver and closes menus rather than closing the window
+    rl.set_exit_key(None);
+    rl.set_window_min_size(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT + config.window.hud_height);
+
+    if let Some(path) = &config.font {
+        if let Err(error) = font::load(&mut rl, &thread, path) {
+            eprintln!("could not load font {}: {}", path.display(), error);
+        }
+    }
+
+    let mut audio = audio::Audio::new(&config, &thread);
+    let mut picture = config.image.as_ref().and_then(|path| {
+        Picture::load(&mut rl, &thread, path)
+            .map_err(|error| eprintln!("could not load image {}: {}", path.display(), error))
+            .ok()
+    });
+    let mut input = Input::new();
+
+    while !rl.window_should_close() && !quit {
+        input.update(&mut rl, &config);
+        let mut menu_action: Option<menu::Action> = None;
+        let mut pause_action: Option<menu::PauseAction> = None;
+        let mut editor_action: Option<editor::EditorAction> = None;
+        let mut settings_change: Option<settings::Change> = None;
+        // cloned, the settings can change them
+        let keybinds = config.keybinds.with_second_player(config.versus);
+
+        // user input
+        if resume_state.is_some() {
+            if rl.is_key_pressed(KeyboardKey::KEY_Y) {
+                (game, completed_level_count) = resume_state.take().unwrap().restore();
+                state = GameState::Playing;
+                fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
+            } else if rl.is_key_pressed(KeyboardKey::KEY_N) {
+                resume_state = None;
+            }
+        } else if let Some(open) = settings.as_mut().filter(|settings| settings.is_rebinding()) {
+            settings_change = open.update(&rl, &input);
+        } else {
+            // escape only pauses if it isn't cancelling the solver
+            let escape_pressed = rl.is_key_pressed(KeyboardKey::KEY_ESCAPE);
+            let pause_pressed = (escape_pressed || input.gamepad.is_pressed(Button::Pause)) && solver_job.is_none();
+
+            if let Some((job, purpose)) = solver_job.take() {
+                if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+                    job.cancel();
+                } else if let Some(result) = job.poll() {
+                    match (purpose, result) {
+                        // the board may have been replaced in the meantime, e.g. by loading a game
+                        _ if job.board().cells != game.board.cells => {}
+                        // boards too far from solved for the solver get the first move of a quick
+                        // solution instead, which usually isn't the best one
+                        (SolvePurpose::Hint, moves) => {
+                            let empty_index = game.board.get_empty_index();
+                            let index = moves
+                                .or_else(|| solver::solve_quickly(&game.board))
+                                .and_then(|moves| moves.first().copied())
+                                .and_then(|direction| game.board.get_neighbor_index(empty_index, direction));
+
+                            if let Some(index) = index {
+                                hint = Some((game.board.cells.clone(), index));
+                            }
+                        }
+                        (SolvePurpose::AutoSolve, Some(moves)) => {
+                            game.start_auto_solve(moves);
+                            next_auto_solve_move = Instant::now();
+                        }
+                        (SolvePurpose::Export, Some(moves)) => copy_to_clipboard(&mut rl, &notation::format(&moves)),
+                        (_, None) => eprintln!("no solution found, the board is too far from solved"),
+                    }
+                } else {
+                    solver_job = Some((job, purpose));
+                }
+            }
+
+            if keybinds.is_pressed(&rl, Action::Settings) {
+                settings = match settings {
+                    Some(_) => None,
+                    None => Some(settings::Settings::new()),
+                };
+                leaderboard = None;
+                online_top_times = None;
+
+                if matches!(state, GameState::Stats { .. }) {
+                    state = state.toggle_stats(&game.board);
+                }
+
+                if audio_settings.take().is_some() {
+                    save_volumes(&volumes);
+                }
+            }
+
+            let control_down =
+                rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+
+            // the local leaderboard comes first, then the online one if a server is set up
+            let screen_open = settings.is_some() || audio_settings.is_some();
+
+            let stats_open = matches!(state, GameState::Stats { .. });
+
+            if keybinds.is_pressed(&rl, Action::Leaderboard) && !control_down && !screen_open && !stats_open {
+                let (width, height) = (game.board.width, game.board.height);
+
+                (leaderboard, online_top_times) = match (leaderboard.take(), &config.online_leaderboard_url) {
+                    (None, _) => {
+                        let records = stats::load().unwrap_or_else(|error| {
+                            eprintln!("{}", error);
+                            Vec::new()
+                        });
+                        (Some(stats::Leaderboard::new(records, width, height)), None)
+                    }
+                    (Some(local), Some(url)) if online_top_times.is_none() => {
+                        (Some(local), Some(online::TopTimes::fetch(url, width, height)))
+                    }
+                    _ => (None, None),
+                };
+            }
+
+            if keybinds.is_pressed(&rl, Action::Statistics) && !control_down && !screen_open && leaderboard.is_none() {
+                state = state.toggle_stats(&game.board);
+            }
+
+            if keybinds.is_pressed(&rl, Action::ExportStatistics) && matches!(state, GameState::Stats { .. }) {
+                let path = PathBuf::from(stats::CSV_EXPORT_PATH);
+
+                match stats::export_csv(&path) {
+                    Ok(count) => println!("exported {} solve(s) to {}", count, path.display()),
+                    Err(error) => eprintln!("{}", error),
+                }
+            }
+
+            let board_covered = screen_open || leaderboard.is_some() || matches!(state, GameState::Stats { .. });
+
+            if keybinds.is_pressed(&rl, Action::AudioSettings) && !board_covered {
+                audio_settings = Some(0);
+            } else if keybinds.is_pressed(&rl, Action::AudioSettings) && audio_settings.take().is_some() {
+                // the volumes are saved once the panel is closed rather than on every change
+                save_volumes(&volumes);
+            }
+
+            if keybinds.is_pressed(&rl, Action::NextTheme) && !control_down {
+                let theme = themes.next(config.theme.as_deref());
+                switch_theme(&mut config, theme);
+            }
+
+            if keybinds.is_pressed(&rl, Action::Thumbnail) && (picture.is_some() || game.target) {
+                config.image_thumbnail = !config.image_thumbnail;
+
+                let shown = config.image_thumbnail;
+                save_setting(|config| config.image_thumbnail = shown);
+            }
 
-struct Board {
-    cells: Vec<i32>,
-    size: u8,
-    solved: bool,
-}
+            if keybinds.is_pressed(&rl, Action::Fullscreen) {
+                toggle_fullscreen(&mut rl, &mut windowed_size, &config);
+            }
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+            if keybinds.is_pressed(&rl, Action::GoalOverlay) {
+                config.goal_overlay = !config.goal_overlay;
 
-impl From<i32> for Direction {
-    fn from(direction: i32) -> Self {
-        match direction {
-            0 => Direction::Up,
-            1 => Direction::Down,
-            2 => Direction::Left,
-            3 => Direction::Right,
-            _ => panic!("Invalid direction"),
-        }
-    }
-}
+                let shown = config.goal_overlay;
+                save_setting(|config| config.goal_overlay = shown);
+            }
 
-pub const BACKGROUND_DARKER: Color = Color {
-    r: 11,
-    g: 11,
-    b: 11,
-    a: 255,
-};
-pub const BACKGROUND: Color = Color {
-    r: 22,
-    g: 22,
-    b: 22,
-    a: 255,
-};
-pub const BACKGROUND_LIGHTER: Color = Color {
-    r: 55,
-    g: 55,
-    b: 55,
-    a: 255,
-};
-pub const TEXT: Color = Color {
-    r: 240,
-    g: 240,
-    b: 240,
-    a: 255,
-};
-pub const BORDER: Color = Color {
-    r: 230,
-    g: 230,
-    b: 230,
-    a: 255,
-};
-pub const MESSAGE_WINDOW_BOUNDS: Rectangle = Rectangle::new(40.0, 140.0, 400.0, 200.0);
-
-impl Board {
-    fn new(cells: Vec<i32>, size: u8) -> Board {
-        let mut board = Board {
-            cells,
-            size,
-            solved: false,
-        };
-        board.check_solved();
-        board
-    }
+            if keybinds.is_pressed(&rl, Action::Mute) {
+                muted = !muted;
 
-    fn scramble(&mut self) {
-        let mut rng = rand::thread_rng();
-        let cells: Vec<i32> = (1..self.size as i32 * self.size as i32 + 1).collect();
-        self.cells = cells;
+                if let Some(audio) = audio.as_mut() {
+                    audio.set_muted(muted);
+                }
+
+                save_setting(|config| config.muted = muted);
+            }
+
+            if let Some(audio) = audio.as_mut() {
+                if keybinds.is_pressed(&rl, Action::NextTrack) {
+                    audio.next_track(&thread);
+                }
 
-        for _i in 0..20 {
-            // to create a random board, we generate a solved board 
-            // and then we performance a random number of legal moves
-            // if we accidentally create a solved board, we try again (limited to 20 attempts)
-            let move_count = rng.gen_range(20..100);
+                if keybinds.is_pressed(&rl, Action::MuteMusic) {
+                    let muted = !audio.is_music_muted();
+                    audio.set_music_muted(muted);
 
-            for _ in 0..move_count {
-                self.move_empty(Direction::from(rng.gen_range(0..4)));
+                    save_setting(|config| config.music_muted = muted);
+                }
             }
 
-            // if we didn't create a solved board, exit
-            if !self.solved {
-                break;
+            if control_down && rl.is_key_pressed(KeyboardKey::KEY_S) {
+                match save::save(&save_path, &save::SaveState::new(&game, completed_level_count)) {
+                    Ok(()) => println!("saved game to {}", save_path.display()),
+                    Err(error) => eprintln!("{}", error),
+                }
+            }
+
+            if control_down && rl.is_key_pressed(KeyboardKey::KEY_L) {
+                match save::load(&save_path) {
+                    Ok(saved) => {
+                        (game, completed_level_count) = saved.restore();
+                        daily_day = None;
+                        state = GameState::Playing;
+                        fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
+                    }
+                    Err(error) => eprintln!("{}", error),
+                }
             }
-        }
-    }
 
-    fn draw(&self, d: &mut RaylibDrawHandle) {
-        let cell_width = 120;
-        let cell_height = 120;
+            if keybinds.is_pressed(&rl, Action::Daily) && settings.is_none() {
+                let day = daily::today();
+                game = Game::new(Board::solved(daily::WIDTH, daily::HEIGHT));
+                game.scramble(daily::DIFFICULTY, Some(daily::seed(day)));
+                daily_day = Some(day);
+                state = GameState::Playing;
+                fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
+            }
 
-        for (i, cell) in self.cells.iter().enumerate() {
-            let x = (i % self.size as usize) as i32 * cell_width;
-            let y = (i / self.size as usize) as i32 * cell_height;
+            // positions are shared in the compact format when the board is small enough for it
+            if control_down && rl.is_key_pressed(KeyboardKey::KEY_C) {
+                let position = game.board.to_compact_string().unwrap_or(game.board.to_string());
+                copy_to_clipboard(&mut rl, &position);
+            }
 
-            let cell_color = if *cell == 16 {
-                BACKGROUND_DARKER
-            } else {
-                BACKGROUND
-            };
+            if control_down && rl.is_key_pressed(KeyboardKey::KEY_V) {
+                let board = rl
+                    .get_clipboard_text()
+                    .map_err(|error| error.to_string())
+                    .and_then(|text| parse_scramble(&text));
 
-            d.draw_rectangle(x, y, cell_width, cell_height, cell_color);
-            d.draw_rectangle_lines(x, y, cell_width, cell_height, BORDER);
+                match board {
+                    Ok(board) => {
+                        game = Game::new(board);
+                        daily_day = None;
+                        state = GameState::Playing;
+                        fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
+                    }
+                    Err(error) => eprintln!("could not paste position: {}", error),
+                }
+            }
+
+            // dropping a picture on the window starts an image puzzle of it at the current board size
+            if rl.is_file_dropped() {
+                let path = rl.load_dropped_files().paths().first().map(PathBuf::from);
+
+                if let Some(path) = path {
+                    match Picture::load(&mut rl, &thread, &path) {
+                        Ok(dropped) => {
+                            picture = Some(dropped);
+                            game = Game::new(Board::solved(game.board.width, game.board.height));
+                            game.scramble(difficulty, None);
+                            daily_day = None;
+                            state = GameState::Playing;
+                        }
+                        Err(error) => eprintln!("could not load image {}: {}", path.display(), error),
+                    }
+                }
+            }
+
+            if keybinds.is_pressed(&rl, Action::CopySeed) && !control_down {
+                if let Some(seed) = game.seed {
+                    copy_to_clipboard(&mut rl, &seed.to_string());
+                }
+            }
+
+            // shift copies the solver's solution for the current board instead of the player's moves
+            let shift_down =
+                rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+
+            if keybinds.is_pressed(&rl, Action::CopyMoves) && !shift_down {
+                copy_to_clipboard(&mut rl, &notation::format(&game.history));
+            }
 
-            if *cell == 16 {
-                continue;
+            if keybinds.is_pressed(&rl, Action::CopyMoves) && shift_down && solver_job.is_none() {
+                let job = solver::SolverJob::start(&game.board, config.solver_heuristic, AUTO_SOLVE_MAX_NODES);
+                solver_job = Some((job, SolvePurpose::Export));
             }
 
-            d.draw_text(
-                &cell.to_string(),
-                x + cell_width / 2 - 10,
-                y + cell_height / 2 - 10,
-                20,
-                TEXT,
-            );
+            if let Some(open) = settings.as_mut() {
+                settings_change = open.update(&rl, &input);
+            } else if let Some(selected) = audio_settings.as_mut() {
+                if rl.is_key_pressed(KeyboardKey::KEY_UP) && *selected > 0 {
+                    *selected -= 1;
+                }
+
+                if rl.is_key_pressed(KeyboardKey::KEY_DOWN) && *selected < audio::Volumes::NAMES.len() - 1 {
+                    *selected += 1;
+                }
+
+                // in steps of 10%
+                let volume = volumes.get_mut(*selected);
+
+                if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+                    *volume = ((*volume * 10.0).round() - 1.0).max(0.0) / 10.0;
+                }
+
+                if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+                    *volume = ((*volume * 10.0).round() + 1.0).min(10.0) / 10.0;
+                }
+
+                // the sliders can be dragged with the mouse as well
+                if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+                    let mouse = rl.get_mouse_position();
+
+                    for index in 0..audio::Volumes::NAMES.len() {
+                        let bar = audio_slider_bounds(rl.get_screen_width(), rl.get_screen_height(), index);
+                        // a little taller than the bar, it is thin
+                        let target = Rectangle::new(bar.x, bar.y - 8.0, bar.width, bar.height + 16.0);
+
+                        if target.check_collision_point_rec(mouse) {
+                            *selected = index;
+                            *volumes.get_mut(index) = ((mouse.x - bar.x) / bar.width).clamp(0.0, 1.0);
+                        }
+                    }
+                }
+
+                if let Some(audio) = audio.as_mut() {
+                    audio.set_volumes(volumes);
+                }
+            } else if leaderboard.is_some() {
+                // the board stays put while it is covered
+            } else {
+                match &mut state {
+                    GameState::Menu(menu) => menu_action = menu.update(&rl, &input.gamepad),
+                    GameState::Editor(editor) => {
+                        let (area, _) = get_board_areas(rl.get_screen_width(), rl.get_screen_height(), false, &config);
+                        let layout = Layout::new(&editor.board, area);
+                        editor_action = editor.update(&mut rl, layout);
+                    }
+                    GameState::Paused(pause) => pause_action = pause.update(&rl, &input.gamepad),
+                    GameState::Stats { statistics, size, .. } => {
+                        let size_count = statistics.sizes.len().max(1);
+
+                        if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+                            *size = (*size + size_count - 1) % size_count;
+                        }
+
+                        if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+                            *size = (*size + 1) % size_count;
+                        }
+                    }
+                    GameState::Scrambling(_) => {
+                        let clicked = rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT);
+
+                        let confirmed = input.gamepad.is_pressed(Button::Confirm);
+
+                        if keybinds.is_pressed(&rl, Action::Continue) || clicked || confirmed {
+                            state = GameState::Playing;
+                        }
+                    }
+                    GameState::Solved => {
+                        if keybinds.is_pressed(&rl, Action::WatchReplay) && !control_down {
+                            let speed = game.playback.as_ref().map_or(args.replay_speed, |playback| playback.speed);
+
+                            if let Some(replay) = replay::Replay::new(&game) {
+                                game = Game::from_replay(replay, speed);
+                                state = GameState::Playing;
+                            }
+                        }
+
+                        let confirmed = input.gamepad.is_pressed(Button::Confirm);
+                        // a number key goes on with a square board of that size, the marathon keeps
+                        // to its own sizes
+                        let resized = input::pressed_board_size(&rl).filter(|_| game.marathon.is_none());
+                        let follows_host = online_race.as_ref().is_some_and(|online_race| online_race.follows_host());
+
+                        // practicing the scramble just solved, a replay is watched again with its own key
+                        let retry = keybinds.is_pressed(&rl, Action::Retry) && game.playback.is_none();
+
+                        if retry && !config.online_race {
+                            pause_action = Some(menu::PauseAction::Restart);
+                        }
+
+                        if keybinds.is_pressed(&rl, Action::Share) && game.playback.is_none() && !game.auto_solved {
+                            copy_to_clipboard(&mut rl, &share::summary(&game, solve_recorder.optimal()));
+                        }
+
+                        // zen mode goes on by itself
+                        let next =
+                            keybinds.is_pressed(&rl, Action::Continue) || confirmed || game.zen || resized.is_some();
+
+                        if next && !follows_host {
+                            // watching a replay doesn't finish another level
+                            if game.playback.is_none() {
+                                completed_level_count += 1;
+                            }
+
+                            if daily_day.take().is_some() {
+                                game = Game::new(Board::solved(width, height));
+                            }
+
+                            if let Some(size) = resized {
+                                (width, height) = (size, size);
+                                game.board = game.board.resized(size, size);
+                            }
+
+                            // the second player won the last race, the first one's board goes back to solved
+                            if !game.board.solved {
+                                game.board = game.board.resized(game.board.width, game.board.height);
+                            }
+
+                            // the marathon goes on with a bigger board, or starts over once it is finished
+                            if let Some(marathon) = game.marathon.as_mut() {
+                                let size = marathon.next_size();
+
+                                if marathon.is_finished() {
+                                    *marathon = Marathon::new();
+                                }
+
+                                game.board = game.board.resized(size, size);
+                            }
+
+                            let level = completed_level_count;
+                            fit_window(&mut rl, &thread, &game.board, level, &mut windowed_size, &config);
+                            game.scramble(difficulty, None);
+                            state = GameState::Playing;
+                        }
+                    }
+                    GameState::Playing => {
+                        if let Some(playback) = game.playback.as_mut().filter(|playback| !playback.is_finished()) {
+                            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
+                                playback.speed = (playback.speed * 2.0).min(replay::MAX_SPEED);
+                            }
+
+                            if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
+                                playback.speed = (playback.speed / 2.0).max(replay::MIN_SPEED);
+                            }
+
+                            game.step_playback(Duration::from_secs_f32(rl.get_frame_time()));
+                        } else if solver_job.is_some() {
+                            // the board has to stay put until the solver is done with it
+                        } else if game.is_auto_solving() {
+                            if keybinds.is_pressed(&rl, Action::AutoSolve) {
+                                game.stop_auto_solve();
+                            } else if Instant::now() >= next_auto_solve_move {
+                                game.step_auto_solve();
+                                next_auto_solve_move = Instant::now() + auto_solve_delay;
+                            }
+                        } else if online_race.as_ref().is_some_and(|online_race| !online_race.is_ready()) {
+                            // the board stays put until both players are there
+                        } else if inspection.ends.is_some() {
+                            // the board can't be moved during the inspection, the player can end it early and
+                            // start the timer with their first move
+                            if keybinds.is_pressed(&rl, Action::Continue) || input.gamepad.is_pressed(Button::Confirm) {
+                                inspection.ends = None;
+                            }
+                        } else if inspection.stackmat_armed {
+                            // the timer starts when the key is let go after it was held long enough, letting go
+                            // too early starts over
+                            match (inspection.stackmat_held, keybinds.is_down(&rl, Action::Continue)) {
+                                (None, true) => inspection.stackmat_held = Some(Instant::now()),
+                                (Some(held_since), false) => {
+                                    inspection.stackmat_held = None;
+
+                                    if held_since.elapsed() >= inspection.stackmat_hold_time {
+                                        inspection.stackmat_armed = false;
+                                        game.timer.start();
+                                    }
+                                }
+                                _ => {}
+                            }
+                        } else if game.is_time_up() {
+                            // another time attack starts from the beginning
+                            if keybinds.is_pressed(&rl, Action::Continue) || input.gamepad.is_pressed(Button::Confirm) {
+                                if let Some(time_attack) = game.time_attack.as_mut() {
+                                    *time_attack = TimeAttack::new(time_attack.time_limit, time_attack.bonus);
+                                }
+
+                                pause_action = Some(menu::PauseAction::NewScramble);
+                            }
+                        } else if game.blindfold_score().is_some() {
+                            // the revealed board stays as it was left until the next one
+                            if keybinds.is_pressed(&rl, Action::Continue) || input.gamepad.is_pressed(Button::Confirm) {
+                                pause_action = Some(menu::PauseAction::NewScramble);
+                            }
+                        } else {
+                            let hinted = hint.as_ref().is_some_and(|(cells, _)| *cells == game.board.cells);
+                            let racing = race.is_some() || versus.is_some() || config.online_race;
+                            update_playing(&mut rl, &mut game, &mut solver_job, &mut input, hinted, racing, &config);
+
+                            if let Some(versus) = versus.as_mut() {
+                                for direction in input.take_second_moves() {
+                                    versus.move_empty(input_direction(direction, &config));
+                                }
+                            }
+
+                            // the timer stops while paused, and the board is hidden so the time can't be used
+                            // to plan
+                            if pause_pressed {
+                                state = GameState::Paused(menu::PauseMenu::new(game.timer.is_running()));
+                                game.timer.stop();
+                            }
+
+                            // the player thinks the board is solved, or gives up
+                            if game.is_blindfolded() && keybinds.is_pressed(&rl, Action::Continue) {
+                                game.reveal();
+                            }
+
+                            // the same as picking it from the pause menu
+                            if keybinds.is_pressed(&rl, Action::Scramble) {
+                                pause_action = Some(menu::PauseAction::NewScramble);
+                            }
+
+                            // an online race can't be started over by one side
+                            if keybinds.is_pressed(&rl, Action::Retry) && !config.online_race {
+                                pause_action = Some(menu::PauseAction::Restart);
+                            }
+                        }
+                    }
+                }
+            }
         }
-    }
 
-    fn check_solved(&mut self) {
-        let mut solved = true;
+        match menu_action {
+            Some(menu::Action::Play(menu_width, menu_height, mode, goal)) => {
+                (width, height) = (menu_width, menu_height);
+                config.solver_race = mode == menu::Mode::Race;
+                config.versus = mode == menu::Mode::Versus;
+
+                if mode == menu::Mode::Daily {
+                    let day = daily::today();
+                    game = Game::new(Board::solved(daily::WIDTH, daily::HEIGHT));
+                    game.scramble(daily::DIFFICULTY, Some(daily::seed(day)));
+                    daily_day = Some(day);
+                } else {
+                    // the marathon starts on the smallest board whatever the size on the menu
+                    let (board_width, board_height) = match mode {
+                        menu::Mode::Marathon => (MIN_BOARD_SIZE, MIN_BOARD_SIZE),
+                        _ => (width, height),
+                    };
+                    // with gravity the empty cell can't ever get down to the bottom row, so it is solved
+                    // in the top corner
+                    let goal = if mode == menu::Mode::Gravity { Goal::BlankFirst } else { goal };
+                    game = Game::new(Board::solved_with_goal(board_width, board_height, goal));
+                    game.board.two_blanks = mode == menu::Mode::TwoBlanks;
+                    game.board.wrap = mode == menu::Mode::Wraparound;
+                    game.board.gravity = mode == menu::Mode::Gravity;
+                    game.target = mode == menu::Mode::Target;
+
+                    if mode == menu::Mode::Letters {
+                        let letters = config.letter_words.iter().flat_map(|word| word.chars());
+                        game.board.letters = letters.filter(|letter| !letter.is_whitespace()).collect();
+                    }
+                    game.endgame = mode == menu::Mode::Endgame;
+                    let memorize_time = Duration::from_secs(config.blindfold_memorize_seconds);
+                    game.blindfold = (mode == menu::Mode::Blindfold).then(|| Blindfold::new(memorize_time));
+                    game.fog = mode == menu::Mode::Fog;
+                    game.marathon = (mode == menu::Mode::Marathon).then(Marathon::new);
+                    game.zen = mode == menu::Mode::Zen;
+                    game.streak = (mode == menu::Mode::Streak).then_some(Streak {
+                        target: Duration::from_secs(config.streak_target_seconds),
+                        current: 0,
+                        best: streak_record.best,
+                    });
 
-        for (i, cell) in self.cells.iter().enumerate() {
-            if *cell != i as i32 + 1 {
-                solved = false;
-                break;
+                    if let (true, Some(audio), Some(dir)) = (game.zen, audio.as_mut(), &config.zen_music_dir) {
+                        audio.play_music_from(&thread, dir);
+                    }
+                    game.time_attack = (mode == menu::Mode::TimeAttack).then(|| {
+                        let time_limit = Duration::from_secs(config.time_attack_seconds);
+                        TimeAttack::new(time_limit, Duration::from_secs(config.time_attack_bonus_seconds))
+                    });
+                    game.scramble(difficulty, None);
+                    daily_day = None;
+                }
+
+                state = GameState::Playing;
+                fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
             }
+            Some(menu::Action::Editor(menu_width, menu_height)) => {
+                (width, height) = (menu_width, menu_height);
+                // positions from the editor are played without the solver racing on them
+                config.solver_race = false;
+                config.versus = false;
+                let editor = editor::Editor::new(width, height);
+                fit_window(&mut rl, &thread, &editor.board, completed_level_count, &mut windowed_size, &config);
+                state = GameState::Editor(editor);
+            }
+            Some(menu::Action::Settings) => settings = Some(settings::Settings::new()),
+            Some(menu::Action::Stats) => state = state.toggle_stats(&game.board),
+            Some(menu::Action::Quit) => quit = true,
+            None => {}
         }
 
-        self.solved = solved;
-    }
+        match editor_action {
+            // the position is played like a pasted one
+            Some(editor::EditorAction::Play(board, solve)) => {
+                game = Game::new(board);
+                daily_day = None;
+                state = GameState::Playing;
 
-    fn get_empty_index(&self) -> usize {
-        self.cells.iter().position(|cell| *cell == 16).unwrap()
-    }
+                if solve {
+                    let job = solver::SolverJob::start(&game.board, config.solver_heuristic, AUTO_SOLVE_MAX_NODES);
+                    solver_job = Some((job, SolvePurpose::AutoSolve));
+                }
+            }
+            Some(editor::EditorAction::Back) => {
+                state = GameState::Menu(menu::Menu::new(width, height, menu::Mode::Classic, game.board.goal));
+            }
+            None => {}
+        }
 
-    fn get_neighbor_index(&self, index: usize, direction: Direction) -> Option<usize> {
-        let row = index / self.size as usize;
-        let col = index % self.size as usize;
+        match pause_action {
+            Some(menu::PauseAction::Resume) => {
+                if matches!(&state, GameState::Paused(pause) if pause.timer_was_running) {
+                    game.timer.start();
+                }
 
-        match direction {
-            Direction::Up => {
-                if row == 0 {
-                    None
-                } else {
-                    Some((row - 1) * self.size as usize + col)
+                state = GameState::Playing;
+            }
+            Some(menu::PauseAction::Restart) => {
+                game = game.restart();
+                // the solver and the second player start over on their boards too
+                race = None;
+                versus = None;
+                state = GameState::Playing;
+            }
+            Some(menu::PauseAction::NewScramble) => {
+                if let Some(streak) = game.streak.as_mut() {
+                    streak.current = 0;
+                }
+
+                // a new scramble of the daily puzzle is just another board
+                if daily_day.take().is_some() {
+                    game = Game::new(Board::solved(width, height));
+                    fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
                 }
+
+                game.scramble(difficulty, None);
+                state = GameState::Playing;
             }
-            Direction::Down => {
-                if row == self.size as usize - 1 {
-                    None
-                } else {
-                    Some((row + 1) * self.size as usize + col)
+            Some(menu::PauseAction::Settings) => settings = Some(settings::Settings::new()),
+            Some(menu::PauseAction::Quit) => quit = true,
+            None => {}
+        }
+
+        match settings_change {
+            Some(settings::Change::Difficulty(step)) => {
+                let index = Difficulty::ALL.iter().position(|d| *d == difficulty).unwrap() as i32;
+                difficulty = Difficulty::ALL[(index + step).clamp(0, Difficulty::ALL.len() as i32 - 1) as usize];
+                save_setting(|config| config.difficulty = difficulty);
+            }
+            Some(settings::Change::Theme(step)) => {
+                let theme = themes.step(config.theme.as_deref(), step);
+                switch_theme(&mut config, theme);
+            }
+            // new games are played at the size, the current one keeps going
+            Some(settings::Change::BoardSize(step)) => {
+                (width, height) = step_board_size(width, height, step);
+                save_setting(|config| (config.width, config.height) = (width, height));
+            }
+            Some(settings::Change::AnimationSpeed(step)) => {
+                config.animation_speed = (config.animation_speed + step as f32 * 0.25).clamp(0.25, 4.0);
+                tile_animation.set_speed(config.animation_speed);
+                race_animation.set_speed(config.animation_speed);
+                scrambles.set_speed(config.animation_speed);
+
+                let speed = config.animation_speed;
+                save_setting(|config| config.animation_speed = speed);
+            }
+            // in steps of 10%, like the audio settings
+            Some(settings::Change::Volume(step)) => {
+                volumes.master = ((volumes.master * 10.0).round() + step as f32).clamp(0.0, 10.0) / 10.0;
+
+                if let Some(audio) = audio.as_mut() {
+                    audio.set_volumes(volumes);
                 }
+
+                save_volumes(&volumes);
             }
-            Direction::Left => {
-                if col == 0 {
-                    None
-                } else {
-                    Some(row * self.size as usize + col - 1)
+            // tints tiles in their solved position and misplaced ones, to see the progress on big boards
+            Some(settings::Change::CorrectnessColors(step)) => {
+                let all = config::CorrectnessColors::ALL;
+                let index = all.iter().position(|colors| *colors == config.correctness_colors).unwrap() as i32;
+                let colors = all[(index + step).clamp(0, all.len() as i32 - 1) as usize];
+                config.correctness_colors = colors;
+                save_setting(|config| config.correctness_colors = colors);
+            }
+            Some(settings::Change::MoveTiles) => {
+                let move_tiles = !config.move_tiles;
+                config.move_tiles = move_tiles;
+                save_setting(|config| config.move_tiles = move_tiles);
+            }
+            Some(settings::Change::WasdKeys) => {
+                let wasd = !config.keybinds.wasd;
+                config.keybinds.wasd = wasd;
+                save_setting(|config| config.keybinds.wasd = wasd);
+            }
+            Some(settings::Change::VimKeys) => {
+                let vim = !config.keybinds.vim;
+                config.keybinds.vim = vim;
+                save_setting(|config| config.keybinds.vim = vim);
+            }
+            Some(settings::Change::NumpadKeys) => {
+                let numpad = !config.keybinds.numpad;
+                config.keybinds.numpad = numpad;
+                save_setting(|config| config.keybinds.numpad = numpad);
+            }
+            Some(settings::Change::Key(action, key, add)) => {
+                config.keybinds.bind(action, key, add);
+                save_setting(|config| config.keybinds.bind(action, key, add));
+            }
+            Some(settings::Change::NewGame) => {
+                settings = None;
+                state = GameState::Playing;
+
+                // leaving the daily puzzle goes back to the regular board size
+                if daily_day.take().is_some() {
+                    game = Game::new(Board::solved(width, height));
+                    fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
                 }
+
+                game.scramble(difficulty, None);
+            }
+            None => {}
+        }
+
+        game.update_gravity();
+
+        // the inspection starts once the scramble was played, and starts the timer once it runs out
+        if let Some(ends) = inspection.ends {
+            if matches!(state, GameState::Scrambling(_)) {
+                inspection.ends = Some(Instant::now() + inspection.duration);
+            } else if matches!(state, GameState::Playing) && Instant::now() >= ends {
+                inspection.ends = None;
+                inspection.stackmat_armed = false;
+                game.timer.start();
+            }
+        }
+
+        // the events of the frame go to everything that reacts to them, in the order they happened
+        events::dispatch(
+            &mut game,
+            &mut [
+                &mut solve_recorder,
+                &mut replay_recorder,
+                &mut target_score,
+                &mut inspection,
+                &mut scrambles,
+                &mut celebration,
+                &mut audio,
+                &mut recent_times,
+                &mut best_splits,
+            ],
+        );
+
+        // the board scrambled behind the menu isn't shown being scrambled
+        if let Some(animation) = scrambles.take().filter(|_| matches!(state, GameState::Playing)) {
+            state = GameState::Scrambling(animation);
+        }
+
+        // a move, the auto-solver or the replay finished the board
+        if matches!(state, GameState::Playing) && game.board.solved {
+            state = GameState::Solved;
+
+            if let Some(versus) = versus.as_mut() {
+                versus.first_solved();
+            }
+
+            if let Some(online_race) = online_race.as_mut() {
+                online_race.solved();
+            }
+
+            if let Some(time_attack) = game.time_attack.as_mut().filter(|_| !game.auto_solved) {
+                time_attack.board_solved();
+            }
+
+            if let Some(marathon) = game.marathon.as_mut() {
+                marathon.solves.push((game.board.width, game.timer.elapsed(), game.move_count));
             }
-            Direction::Right => {
-                if col == self.size as usize - 1 {
-                    None
+
+            // a solve over the target time breaks the streak, as does letting the computer solve it
+            if let Some(streak) = game.streak.as_mut() {
+                if game.auto_solved || game.timer.elapsed() > streak.target {
+                    streak.current = 0;
                 } else {
-                    Some(row * self.size as usize + col + 1)
+                    streak.current += 1;
+                }
+
+                if streak.current > streak_record.best {
+                    streak.best = streak.current;
+                    streak_record.best = streak.current;
+
+                    if let Err(error) = streak::save(&streak_record) {
+                        eprintln!("{}", error);
+                    }
                 }
             }
         }
-    }
 
-    fn move_empty(&mut self, direction: Direction) {
-        let empty_index = self.get_empty_index();
-        let neighbor_index = self.get_neighbor_index(empty_index, direction);
+        // the other player solved their board first, that ends this one's game as well
+        let second_won = versus.as_ref().is_some_and(|versus| versus.first_won() == Some(false))
+            || online_race.as_ref().is_some_and(|online_race| online_race.first_won() == Some(false));
 
-        if let Some(neighbor_index) = neighbor_index {
-            self.cells.swap(empty_index, neighbor_index);
+        if matches!(state, GameState::Playing) && second_won {
+            state = GameState::Solved;
+            game.timer.stop();
         }
 
-        self.check_solved();
-    }
-}
+        // the time attack's clock only runs while a board is played, until it runs out
+        if let Some(time_attack) = game.time_attack.as_mut() {
+            if matches!(state, GameState::Playing) && !time_attack.is_over() {
+                time_attack.clock.start();
+            } else {
+                time_attack.clock.stop();
+            }
+        }
 
-fn format_window_title(level_index: i32) -> String {
-    "15 Puzzle - Level ".to_owned() + &(level_index + 1).to_string()
-}
+        if let Some(audio) = audio.as_mut() {
+            audio.update(&thread);
+        }
 
-fn main() {
-    let mut completed_level_count: i32 = 0;
-    let mut board = Board::new([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16].to_vec(), 4);
-    board.scramble();
+        solve_recorder.update(&game);
+        target_score.update(&game);
 
-    let (mut rl, thread) = raylib::init()
-        .size(480, 480)
-        .title(&format_window_title(completed_level_count))
-        .build();
+        // auto-solved boards and replays don't count towards the daily streak
+        let daily_completed = game.board.solved && game.counts_for_stats();
 
-    rl.set_target_fps(30);
+        // shot up from the middle of the player's board, the race board is to the right of it
+        let board_width = rl.get_screen_width() as f32 / if race.is_some() { 2.0 } else { 1.0 };
+        let (x, y) = (board_width / 2.0, rl.get_screen_height() as f32 / 2.0);
+        celebration.update(rl.get_frame_time(), x, y, rl.get_screen_height() as f32);
 
-    while !rl.window_should_close() {
-        // user input
-        if board.solved {
-            if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
-                completed_level_count += 1;
-                rl.set_window_title(&thread, &format_window_title(completed_level_count));
-                board.scramble();
+        if let Some(day) = daily_day.filter(|day| daily_completed && !daily_record.is_completed(*day)) {
+            daily_record.complete(day);
+
+            if let Err(error) = daily::save(&daily_record) {
+                eprintln!("could not save daily record: {}", error);
+            }
+        }
+
+        let board_size = (game.board.width, game.board.height);
+
+        if recent_times.as_ref().map(|times| (times.width, times.height)) != Some(board_size) {
+            let records = stats::load().unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                Vec::new()
+            });
+            recent_times = Some(stats::RecentTimes::new(&records, board_size.0, board_size.1));
+        }
+
+        // only seeded boards are likely to have been played before, and variants have no ghost
+        let variant = game.zen || game.target || game.board.is_variant();
+        if config.ghost_race && game.seed.is_some() && game.playback.is_none() && !variant {
+            let start = game.start_board();
+
+            if ghost_start.as_ref() != Some(&start.cells) {
+                ghost = replay::find_best(&start).map(replay::Ghost::new);
+                ghost_start = Some(start.cells);
+            }
+        } else {
+            ghost = None;
+            ghost_start = None;
+        }
+
+        if config.solver_race {
+            let start = game.start_board();
+
+            if race.as_ref().map(|race| &race.start().cells) != Some(&start.cells) {
+                race = Some(race::Race::new(
+                    &start,
+                    config.solver_heuristic,
+                    AUTO_SOLVE_MAX_NODES,
+                    config.solver_race_moves_per_second,
+                ));
+            }
+
+            if let Some(race) = race.as_mut() {
+                race.update(game.timer.elapsed());
+            }
+        } else {
+            race = None;
+        }
+
+        // the second player gets the same scramble as the first one
+        if config.versus {
+            let start = game.start_board();
+
+            if versus.as_ref().map(|versus| &versus.start().cells) != Some(&start.cells) {
+                versus = Some(versus::Versus::new(&start));
+            }
+
+            if let Some(versus) = versus.as_mut() {
+                versus.update(matches!(state, GameState::Playing));
             }
         } else {
-            if rl.is_key_pressed(KeyboardKey::KEY_UP) {
-                board.move_empty(Direction::Up);
+            versus = None;
+        }
+
+        // the guest plays the host's scramble as soon as the host does
+        if let Some(start) = online_race.as_mut().and_then(|online_race| online_race.update(&game, difficulty)) {
+            (width, height) = (start.width, start.height);
+            game = Game::new(Board::solved(width, height));
+            game.scramble(start.difficulty, Some(start.seed));
+            daily_day = None;
+            state = GameState::Playing;
+            fit_window(&mut rl, &thread, &game.board, completed_level_count, &mut windowed_size, &config);
+        }
+
+        // chat's move is made at the end of every round of voting, and the round starts over while
+        // the board can't be played
+        if let Some(chat) = chat.as_mut() {
+            let playable = matches!(state, GameState::Playing) && game.playback.is_none() && !game.is_auto_solving();
+            let playable = playable && !inspection.is_waiting();
+
+            if let Some(direction) = chat.update().filter(|_| playable) {
+                game.events.publish(Event::MoveRequested(input_direction(direction, &config)));
+                game.chat_moved = true;
+            }
+
+            if !playable {
+                chat.restart_round();
             }
+        }
+
+        // a spectator's own board shows the host's
+        if let Some(host_board) = online_race.as_ref().and_then(|online_race| online_race.host_board()) {
+            game.board = host_board.board.clone();
+            game.move_count = host_board.moves;
+            game.timer = Timer::with_elapsed(host_board.time);
+        }
 
-            if rl.is_key_pressed(KeyboardKey::KEY_DOWN) {
-                board.move_empty(Direction::Down);
+        // theme files can be edited while playing, the current theme is applied again once they change
+        if themes.reload_if_changed() {
+            if let Some(theme) = config.theme.as_deref().and_then(|id| themes.get(id)) {
+                config.colors = theme.colors.clone();
             }
+        }
 
-            if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
-                board.move_empty(Direction::Left);
+        if let GameState::Scrambling(animation) = &mut state {
+            animation.update();
+        }
+
+        if matches!(&state, GameState::Scrambling(animation) if animation.is_finished_for(&game.board)) {
+            state = GameState::Playing;
+        }
+
+        // the scramble is shown in place of the game's board while it plays
+        let shown_board = match &state {
+            GameState::Editor(editor) => &editor.board,
+            _ => state.scramble_animation().map_or(&game.board, |animation| &animation.board),
+        };
+
+        // nothing slides without updates, the tiles are drawn right in their cells
+        if !config.reduced_motion {
+            tile_animation.update(shown_board);
+
+            if let Some(race) = &race {
+                race_animation.update(&race.board);
             }
 
-            if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
-                board.move_empty(Direction::Right);
+            if let Some(versus) = &versus {
+                race_animation.update(&versus.board);
             }
         }
 
         // draw
         let mut d = rl.begin_drawing(&thread);
-        d.clear_background(BACKGROUND_DARKER);
-        board.draw(&mut d);
-
-        if board.solved {
-            d.draw_rectangle_rec(MESSAGE_WINDOW_BOUNDS, BACKGROUND_LIGHTER);
-            d.draw_rectangle_lines_ex(MESSAGE_WINDOW_BOUNDS, 2.0, BORDER);
-
-            d.draw_text(
-                "You win!\nPress [SPACE] to continue",
-                MESSAGE_WINDOW_BOUNDS.x as i32 + 10,
-                MESSAGE_WINDOW_BOUNDS.y as i32 + 10,
-                28,
-                Color::WHITE,
-            );
+        d.clear_background(config.colors.background_darker);
+
+        let (screen_width, screen_height) = (d.get_screen_width(), d.get_screen_height());
+        let split = race.is_some() || versus.is_some() || config.online_race;
+        let (player_area, race_area) = get_board_areas(screen_width, screen_height, split, &config);
+        let layout = Layout::new(shown_board, player_area);
+        let visibility = if state.is_menu() { Visibility::All } else { game.visibility(&config) };
+        draw_board(&mut d, shown_board, layout, &tile_animation, picture.as_ref(), visibility, &config);
+
+        if let Some(race) = &race {
+            draw_race(&mut d, race_area, race, &race_animation, picture.as_ref(), &config);
+        }
+
+        if let Some(versus) = &versus {
+            let status = "Player 2 [".to_owned()
+                + &input::second_player_key_names()
+                + "]  Moves: "
+                + &versus.move_count.to_string()
+                + "  Time: "
+                + &format_duration(versus.time());
+            let labels = ["Player 1", &status];
+            draw_opponent(&mut d, race_area, &versus.board, labels, &race_animation, picture.as_ref(), &config);
+        }
+
+        if let Some(online_race) = online_race.as_ref().filter(|online_race| online_race.opponent().is_some()) {
+            let opponent = online_race.opponent().unwrap();
+            let watching = online_race.role() == multiplayer::Role::Spectator;
+            let (name, other) = if watching { ("Host", "Guest") } else { ("You", "Opponent") };
+            let status = if online_race.is_closed() {
+                "The other player left".to_owned()
+            } else {
+                other.to_owned() + "  Moves: " + &opponent.moves.to_string()
+            };
+            let labels = [name, &status];
+            draw_opponent(&mut d, race_area, &opponent.board, labels, &race_animation, picture.as_ref(), &config);
+        }
+
+        let by_hand = game.playback.is_none() && !game.is_auto_solving();
+
+        if matches!(state, GameState::Playing) && by_hand {
+            draw_hover(&mut d, &game.board, layout, &config);
+        }
+
+        let hint_held = config.keybinds.is_down(&d, Action::Hint);
+
+        if let Some((_, index)) = hint.as_ref().filter(|(cells, _)| hint_held && *cells == game.board.cells) {
+            draw_hint(&mut d, &game.board, *index, layout, &config);
+        }
+
+        if let Some(picture) = picture.as_ref().filter(|_| config.image_thumbnail) {
+            draw_thumbnail(&mut d, &game.board, picture, layout, &config);
+        }
+
+        if game.target && !state.is_menu() && config.image_thumbnail {
+            draw_target(&mut d, &game.board, layout, &config);
+        }
+
+        draw_hud(&mut d, &game, daily_day, recent_times.as_ref(), &config);
+
+        if let Some(ghost) = &ghost {
+            draw_ghost_race(&mut d, &game, ghost, &config);
+        }
+
+        if let Some(chat) = &chat {
+            draw_chat_votes(&mut d, chat, &config);
+        }
+
+        if let (Some(ends), GameState::Playing) = (inspection.ends, &state) {
+            draw_inspection(&mut d, ends.saturating_duration_since(Instant::now()), &config);
+        } else if let (true, GameState::Playing) = (inspection.stackmat_armed, &state) {
+            let held = inspection.stackmat_held;
+            let ready = held.is_some_and(|held_since| held_since.elapsed() >= inspection.stackmat_hold_time);
+            draw_stackmat(&mut d, held.is_some(), ready, &config);
+        }
+
+        let timed = matches!(state, GameState::Playing | GameState::Solved) && game.counts_for_stats();
+
+        if config.show_splits && timed {
+            draw_splits(&mut d, &game, best_splits.compared_to(&game), player_area, &config);
+        }
+
+        if let Some(burst) = &celebration.burst {
+            burst.draw(&mut d);
+        }
+
+        // the menu and the pause menu cover the board, the other screens are drawn over them
+        match &state {
+            GameState::Menu(menu) => menu.draw(&mut d, &config),
+            GameState::Paused(pause) => pause.draw(&mut d, &config),
+            GameState::Editor(editor) => editor.draw(&mut d, layout, &config),
+            _ => {}
+        }
+
+        if resume_state.is_some() {
+            draw_message_window(&mut d, "Resume previous game?\n[Y] yes  [N] no", &config);
+        } else if let Some(waiting) = online_race.as_ref().and_then(|online_race| online_race.waiting_for()) {
+            draw_message_window(&mut d, waiting, &config);
+        } else if solver_job.is_some() {
+            let spinner = ["|", "/", "-", "\\"][(d.get_time() * 8.0) as usize % 4];
+            draw_message_window(&mut d, &("Solving ".to_owned() + spinner + "\n[ESC] cancel"), &config);
+        } else if let Some(settings) = &settings {
+            let theme = config.theme.as_deref().and_then(|id| themes.get(id));
+            let theme = theme.map_or("Custom", |theme| &theme.name);
+            settings.draw(&mut d, difficulty, theme, (width, height), volumes.master, &config);
+        } else if let Some(selected) = audio_settings {
+            draw_audio_settings(&mut d, &volumes, selected, muted, &config);
+        } else if let Some(top_times) = online_top_times.as_mut() {
+            draw_online_leaderboard(&mut d, &game.board, top_times, &config);
+        } else if let Some(leaderboard) = &leaderboard {
+            draw_leaderboard(&mut d, leaderboard, &config);
+        } else {
+            match &state {
+                GameState::Stats { statistics, size, .. } => {
+                    draw_statistics(&mut d, &game.board, statistics, *size, streak_record.best, &config)
+                }
+                GameState::Playing if game.is_time_up() => {
+                    let solved = game.time_attack.as_ref().map_or(0, |time_attack| time_attack.boards_solved);
+                    let key = config.keybinds.key_name(Action::Continue);
+                    let text = format!("Time's up! {} board(s) solved\n[{}] play again", solved, key);
+                    draw_message_window(&mut d, &text, &config);
+                }
+                GameState::Playing if game.blindfold_score().is_some() => {
+                    let (in_place, tiles) = game.blindfold_score().unwrap();
+                    let key = config.keybinds.key_name(Action::Continue);
+                    let text = format!("{} of {} tiles in place\n[{}] next board", in_place, tiles, key);
+                    draw_message_window(&mut d, &text, &config);
+                }
+                // zen boards are scrambled again before the message would be read
+                GameState::Solved if game.zen => {}
+                GameState::Solved if game.target && game.playback.is_none() => {
+                    draw_target_reached(&mut d, &game, target_score.optimal, &config);
+                }
+                GameState::Solved if versus.is_some() && game.playback.is_none() => {
+                    let versus = versus.as_ref().unwrap();
+                    let winner = if versus.first_won() == Some(false) { "Player 2" } else { "Player 1" };
+                    let results = [(game.timer.elapsed(), game.move_count), (versus.time(), versus.move_count)];
+                    let names = ["Player 1", "Player 2"];
+                    draw_race_result(&mut d, &(winner.to_owned() + " wins!"), names, results, true, &config);
+                }
+                GameState::Solved if online_race.is_some() && game.playback.is_none() => {
+                    let online_race = online_race.as_ref().unwrap();
+                    let watching = online_race.role() == multiplayer::Role::Spectator;
+                    let title = match (online_race.first_won(), watching) {
+                        (Some(false), false) => "The other player won!",
+                        (_, false) => "You won!",
+                        (Some(false), true) => "The guest won!",
+                        (_, true) => "The host won!",
+                    };
+                    let names = if watching { ["Host", "Guest"] } else { ["You", "Opponent"] };
+                    let opponent = online_race.opponent();
+                    let opponent = opponent.map_or((Duration::ZERO, 0), |opponent| (opponent.time, opponent.moves));
+                    let results = [(game.timer.elapsed(), game.move_count), opponent];
+                    let follows_host = online_race.follows_host();
+                    draw_race_result(&mut d, title, names, results, !follows_host, &config);
+                }
+                GameState::Solved => {
+                    let daily = daily_day.map(|day| daily_record.current_streak(day));
+                    draw_solved(&mut d, &game, daily, solve_recorder.record.as_ref(), race.as_ref(), &config);
+                }
+                GameState::Menu(_)
+                | GameState::Editor(_)
+                | GameState::Scrambling(_)
+                | GameState::Playing
+                | GameState::Paused(_) => {}
+            }
+        }
+
+        // taken before the toast is drawn, so the last one isn't in the picture
+        if keybinds.is_pressed(&d, Action::Screenshot) {
+            let text = match screenshot::save(&d, &thread) {
+                Ok(path) => "Saved ".to_owned() + &path.display().to_string(),
+                Err(error) => error,
+            };
+            toast = Some((text, Instant::now()));
+        }
+
+        if let Some((text, shown_at)) = &toast {
+            if shown_at.elapsed() < TOAST_DURATION {
+                draw_toast(&mut d, text, &config);
+            }
+        }
+    }
+
+    solve_recorder.finish();
+
+    // keep unfinished games around so they can be resumed on the next launch,
+    // unless the player quit without answering the resume prompt, quit from the menu without playing
+    // or was only watching a replay or someone else's race
+    let watching = online_race.is_some_and(|online_race| online_race.role() == multiplayer::Role::Spectator);
+    let playing = resume_state.is_none() && !state.is_menu() && game.playback.is_none() && !watching;
+
+    if let Some(autosave_path) = autosave_path.filter(|_| playing) {
+        let result = if game.board.solved {
+            storage::remove_file(&autosave_path).or_else(|error| match error.kind() {
+                std::io::ErrorKind::NotFound => Ok(()),
+                _ => Err(error.to_string()),
+            })
+        } else {
+            save::save(&autosave_path, &save::SaveState::new(&game, completed_level_count))
+        };
+
+        if let Err(error) = result {
+            eprintln!("could not autosave: {}", error);
         }
     }
 }
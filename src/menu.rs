@@ -0,0 +1,333 @@
+use crate::config::Config;
+use crate::input::{self, Button, Gamepad};
+use crate::{font, step_board_size, Direction, Goal};
+use raylib::prelude::*;
+
+// what kind of game play starts
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    Classic,
+    // the solver plays the same scramble on a board next to the player's
+    Race,
+    // two players at the same keyboard race on boards side by side, one with the arrow keys and one
+    // with w a s d
+    Versus,
+    // today's daily puzzle, which is always the same size
+    Daily,
+    // only the last two rows are scrambled, to practice the hardest part of the puzzle
+    Endgame,
+    // the numbers are hidden once the board was memorized, it has to be solved from memory
+    Blindfold,
+    // only the tiles close to the empty cell can be seen
+    Fog,
+    // as many boards as possible before the clock runs out, every solve adds time to it
+    TimeAttack,
+    // one board of every size from 3x3 up, timed all together
+    Marathon,
+    // no timer and no move counter, solved boards are scrambled again right away
+    Zen,
+    // boards in a row, each solved within a target time
+    Streak,
+    // the two highest values are both empty, and either empty cell can be moved
+    TwoBlanks,
+    // moves off one edge of the board come back in on the other
+    Wraparound,
+    // the tiles are letters that spell words row by row
+    Letters,
+    // the tiles above the empty cell fall into it after every move
+    Gravity,
+    // the board starts solved, and a scramble of it has to be reached in as few moves as possible
+    Target,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 16] = [
+        Mode::Classic,
+        Mode::Race,
+        Mode::Versus,
+        Mode::Daily,
+        Mode::Endgame,
+        Mode::Blindfold,
+        Mode::Fog,
+        Mode::TimeAttack,
+        Mode::Marathon,
+        Mode::Zen,
+        Mode::Streak,
+        Mode::TwoBlanks,
+        Mode::Wraparound,
+        Mode::Letters,
+        Mode::Gravity,
+        Mode::Target,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Mode::Classic => "Classic",
+            Mode::Race => "Race the computer",
+            Mode::Versus => "Two players",
+            Mode::Daily => "Daily puzzle",
+            Mode::Endgame => "Endgame trainer",
+            Mode::Blindfold => "Blindfold",
+            Mode::Fog => "Fog of war",
+            Mode::TimeAttack => "Time attack",
+            Mode::Marathon => "Marathon",
+            Mode::Zen => "Zen",
+            Mode::Streak => "Streak",
+            Mode::TwoBlanks => "Two blanks",
+            Mode::Wraparound => "Wraparound",
+            Mode::Letters => "Letters",
+            Mode::Gravity => "Gravity",
+            Mode::Target => "Reach the target",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Item {
+    Play,
+    BoardSize,
+    Mode,
+    Goal,
+    Editor,
+    Settings,
+    Stats,
+    Quit,
+}
+
+const ITEMS: [Item; 8] = [
+    Item::Play,
+    Item::BoardSize,
+    Item::Mode,
+    Item::Goal,
+    Item::Editor,
+    Item::Settings,
+    Item::Stats,
+    Item::Quit,
+];
+
+// what the menu asks the game to do once an item is picked
+#[derive(Clone, Copy)]
+pub enum Action {
+    Play(u8, u8, Mode, Goal),
+    // arrange a position of the board size by hand
+    Editor(u8, u8),
+    Settings,
+    Stats,
+    Quit,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PauseAction {
+    Resume,
+    // the same scramble again, from the start
+    Restart,
+    NewScramble,
+    Settings,
+    Quit,
+}
+
+const PAUSE_ITEMS: [(PauseAction, &str); 5] = [
+    (PauseAction::Resume, "Resume"),
+    (PauseAction::Restart, "Restart"),
+    (PauseAction::NewScramble, "New scramble"),
+    (PauseAction::Settings, "Settings"),
+    (PauseAction::Quit, "Quit"),
+];
+
+// the title screen the game starts on, unless a game was asked for on the command line
+pub struct Menu {
+    selected: usize,
+    width: u8,
+    height: u8,
+    mode: Mode,
+    // the order the tiles have to be put in, the daily puzzle is always solved row by row
+    goal: Goal,
+}
+
+impl Menu {
+    pub fn new(width: u8, height: u8, mode: Mode, goal: Goal) -> Menu {
+        Menu {
+            selected: 0,
+            width,
+            height,
+            mode,
+            goal,
+        }
+    }
+
+    // the arrow keys or the d-pad pick an item and change the board size, mode and goal, enter, a
+    // click or the confirm button uses it. escape quits from here
+    pub fn update(&mut self, rl: &RaylibHandle, gamepad: &Gamepad) -> Option<Action> {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) {
+            return Some(Action::Quit);
+        }
+
+        // a number key plays a square board of that size right away
+        if let Some(size) = input::pressed_board_size(rl) {
+            (self.width, self.height) = (size, size);
+            return Some(Action::Play(size, size, self.mode, self.goal));
+        }
+
+        let used = select_item(rl, gamepad, &mut self.selected, ITEMS.len());
+
+        if rl.is_key_pressed(KeyboardKey::KEY_LEFT) || gamepad.is_pressed(Button::Move(Direction::Left)) {
+            self.change(ITEMS[self.selected], -1);
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) || gamepad.is_pressed(Button::Move(Direction::Right)) {
+            self.change(ITEMS[self.selected], 1);
+        }
+
+        if !used {
+            return None;
+        }
+
+        match ITEMS[self.selected] {
+            Item::Play => Some(Action::Play(self.width, self.height, self.mode, self.goal)),
+            // clicking the board size, mode or goal steps it on, enter does too
+            item @ (Item::BoardSize | Item::Mode | Item::Goal) => {
+                self.change(item, 1);
+                None
+            }
+            Item::Editor => Some(Action::Editor(self.width, self.height)),
+            Item::Settings => Some(Action::Settings),
+            Item::Stats => Some(Action::Stats),
+            Item::Quit => Some(Action::Quit),
+        }
+    }
+
+    fn change(&mut self, item: Item, step: i32) {
+        match item {
+            Item::BoardSize => (self.width, self.height) = step_board_size(self.width, self.height, step),
+            Item::Mode => {
+                let index = Mode::ALL.iter().position(|mode| *mode == self.mode).unwrap() as i32;
+                self.mode = Mode::ALL[(index + step).rem_euclid(Mode::ALL.len() as i32) as usize];
+            }
+            Item::Goal => {
+                let index = Goal::ALL.iter().position(|goal| *goal == self.goal).unwrap() as i32;
+                self.goal = Goal::ALL[(index + step).rem_euclid(Goal::ALL.len() as i32) as usize];
+            }
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle, config: &Config) {
+        // the daily puzzle has a size of its own
+        let size = if self.mode == Mode::Daily {
+            "daily".to_owned()
+        } else {
+            format!("{}x{}", self.width, self.height)
+        };
+
+        let texts: Vec<String> = ITEMS
+            .iter()
+            .map(|item| match item {
+                Item::Play => "Play".to_owned(),
+                Item::BoardSize => format!("Board size: < {} >", size),
+                Item::Mode => format!("Mode: < {} >", self.mode.name()),
+                Item::Goal => format!("Goal: < {} >", self.goal.name()),
+                Item::Editor => "Position editor".to_owned(),
+                Item::Settings => "Settings".to_owned(),
+                Item::Stats => "Stats".to_owned(),
+                Item::Quit => "Quit".to_owned(),
+            })
+            .collect();
+
+        draw_items(d, "Fifteen Puzzle", &texts, self.selected, config);
+    }
+}
+
+// shown over the board while the game is paused, hiding it so the next moves can't be planned
+// with the timer stopped
+pub struct PauseMenu {
+    selected: usize,
+    // the timer keeps stopped on resuming if the first move wasn't made yet
+    pub timer_was_running: bool,
+}
+
+impl PauseMenu {
+    pub fn new(timer_was_running: bool) -> PauseMenu {
+        PauseMenu {
+            selected: 0,
+            timer_was_running,
+        }
+    }
+
+    // escape or the pause button resumes, like it paused
+    pub fn update(&mut self, rl: &RaylibHandle, gamepad: &Gamepad) -> Option<PauseAction> {
+        if rl.is_key_pressed(KeyboardKey::KEY_ESCAPE) || gamepad.is_pressed(Button::Pause) {
+            return Some(PauseAction::Resume);
+        }
+
+        select_item(rl, gamepad, &mut self.selected, PAUSE_ITEMS.len()).then_some(PAUSE_ITEMS[self.selected].0)
+    }
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle, config: &Config) {
+        let texts: Vec<String> = PAUSE_ITEMS.iter().map(|(_, text)| text.to_string()).collect();
+        draw_items(d, "Paused", &texts, self.selected, config);
+    }
+}
+
+// moves the selection with the up and down arrows or the d-pad, returns whether the selected item
+// was used with enter, the confirm button or by clicking it
+fn select_item(rl: &RaylibHandle, gamepad: &Gamepad, selected: &mut usize, count: usize) -> bool {
+    let up = rl.is_key_pressed(KeyboardKey::KEY_UP) || gamepad.is_pressed(Button::Move(Direction::Up));
+    let down = rl.is_key_pressed(KeyboardKey::KEY_DOWN) || gamepad.is_pressed(Button::Move(Direction::Down));
+
+    if up && *selected > 0 {
+        *selected -= 1;
+    }
+
+    if down && *selected < count - 1 {
+        *selected += 1;
+    }
+
+    if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+        let (screen_width, screen_height) = (rl.get_screen_width(), rl.get_screen_height());
+        let mouse = rl.get_mouse_position();
+        let clicked = (0..count)
+            .find(|index| item_bounds(screen_width, screen_height, *index, count).check_collision_point_rec(mouse));
+
+        if let Some(index) = clicked {
+            *selected = index;
+            return true;
+        }
+    }
+
+    rl.is_key_pressed(KeyboardKey::KEY_ENTER) || gamepad.is_pressed(Button::Confirm)
+}
+
+// covers the whole window, with the title above the items
+fn draw_items(d: &mut RaylibDrawHandle, title: &str, texts: &[String], selected: usize, config: &Config) {
+    let colors = &config.colors;
+    let (screen_width, screen_height) = (d.get_screen_width(), d.get_screen_height());
+    d.draw_rectangle(0, 0, screen_width, screen_height, colors.background_darker);
+
+    let title_y = item_bounds(screen_width, screen_height, 0, texts.len()).y as i32 - 80;
+    font::draw_text(d, title, (screen_width - font::measure_text(title, 40)) / 2, title_y, 40, colors.text);
+
+    for (index, text) in texts.iter().enumerate() {
+        let bounds = item_bounds(screen_width, screen_height, index, texts.len());
+
+        if index == selected {
+            d.draw_rectangle_rec(bounds, colors.background_lighter);
+            d.draw_rectangle_lines_ex(bounds, 2.0, colors.border);
+        }
+
+        let x = bounds.x as i32 + (bounds.width as i32 - font::measure_text(text, 20)) / 2;
+        font::draw_text(d, text, x, bounds.y as i32 + 8, 20, colors.text);
+    }
+}
+
+// the items are stacked in the middle of the window and can be clicked
+fn item_bounds(screen_width: i32, screen_height: i32, index: usize, count: usize) -> Rectangle {
+    let (width, height, gap) = (300.0_f32.min(screen_width as f32 - 20.0), 36.0, 8.0);
+    let top = (screen_height as f32 - count as f32 * (height + gap)) / 2.0 + 30.0;
+
+    Rectangle::new(
+        (screen_width as f32 - width) / 2.0,
+        top + index as f32 * (height + gap),
+        width,
+        height,
+    )
+}
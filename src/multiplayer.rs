@@ -0,0 +1,413 @@
+use crate::{Board, Difficulty, Game};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+// a race between two games over the network. one of them hosts it on a port and the other joins by
+// address, then they send each other json messages over tcp, one on each line. the host picks the
+// scrambles and the guest plays whatever the host is playing. anyone else can connect to the host
+// to watch, the host sends them both boards
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// messages are written from the game loop, a peer that stops reading is dropped rather than
+// freezing the game
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// a race on a board of the size, scrambled with the seed
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RaceStart {
+    pub width: u8,
+    pub height: u8,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+}
+
+// a player's board in the race with the seed, sent after every change to it. the whole board is
+// sent rather than the moves, so undos, falls and a missed message don't get the boards out of step
+#[derive(Clone, Serialize, Deserialize)]
+struct Progress {
+    seed: u64,
+    cells: Vec<i32>,
+    moves: u32,
+    time_ms: u64,
+    // whose board it is, spectators get both
+    #[serde(default)]
+    host: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    // the first message to the host, whether the game connecting races or only watches
+    Hello { watch: bool },
+    Start(RaceStart),
+    Progress(Progress),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Role {
+    Host,
+    Guest,
+    Spectator,
+}
+
+struct Connection {
+    stream: TcpStream,
+    receiver: Receiver<Message>,
+}
+
+enum Link {
+    // the host waits for someone to join
+    Waiting,
+    Connected(Connection),
+    // the other player left, or the connection broke
+    Closed,
+}
+
+// a player's board as the last progress left it
+pub struct RemoteBoard {
+    seed: u64,
+    pub board: Board,
+    pub moves: u32,
+    pub time: Duration,
+}
+
+pub struct OnlineRace {
+    role: Role,
+    // the host's connection to the guest, or the guest's and the spectator's to the host
+    link: Link,
+    // the games connecting to the host, and those that said they only watch
+    incoming: Option<Receiver<TcpStream>>,
+    greeting: Vec<Connection>,
+    spectators: Vec<TcpStream>,
+    // the race being played, the host's latest scramble
+    race: Option<RaceStart>,
+    // the latest progress of each player that wasn't shown yet
+    progress: Option<Progress>,
+    host_progress: Option<Progress>,
+    // the other player's board, and a spectator's view of the host's
+    opponent: Option<RemoteBoard>,
+    host_board: Option<RemoteBoard>,
+    // this player's progress and the guest's last sent, for spectators joining in the middle of a race
+    sent: Option<Progress>,
+    relayed: Option<Progress>,
+    // whether this player solved their board before the other one did, once either did. spectators
+    // count as the host
+    first_won: Option<bool>,
+}
+
+impl OnlineRace {
+    pub fn host(port: u16) -> Result<OnlineRace, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .map_err(|error| format!("could not host on port {}: {}", port, error))?;
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if sender.send(stream).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut online_race = OnlineRace::new(Role::Host, Link::Waiting);
+        online_race.incoming = Some(receiver);
+        Ok(online_race)
+    }
+
+    // joins the race hosted at the address, as the other player or to watch it
+    pub fn join(address: &str, watch: bool) -> Result<OnlineRace, String> {
+        let error = |error: String| format!("could not join {}: {}", address, error);
+        let socket_address = address
+            .to_socket_addrs()
+            .map_err(|io_error| error(io_error.to_string()))?
+            .next()
+            .ok_or_else(|| error("unknown address".to_owned()))?;
+        let stream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)
+            .map_err(|io_error| error(io_error.to_string()))?;
+
+        let link = match Connection::open(stream) {
+            Some(connection) => Link::Connected(connection),
+            None => Link::Closed,
+        };
+        let role = if watch { Role::Spectator } else { Role::Guest };
+        let mut online_race = OnlineRace::new(role, link);
+        online_race.send(&Message::Hello { watch });
+        Ok(online_race)
+    }
+
+    fn new(role: Role, link: Link) -> OnlineRace {
+        OnlineRace {
+            role,
+            link,
+            incoming: None,
+            greeting: Vec::new(),
+            spectators: Vec::new(),
+            race: None,
+            progress: None,
+            host_progress: None,
+            opponent: None,
+            host_board: None,
+            sent: None,
+            relayed: None,
+            first_won: None,
+        }
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    // keeps up with the other player, and tells the guest and spectators which race to play once
+    // they aren't playing that one yet
+    pub fn update(&mut self, game: &Game, difficulty: Difficulty) -> Option<RaceStart> {
+        self.accept();
+        self.receive();
+
+        if let (Role::Host, Some(seed)) = (self.role, game.seed) {
+            let (width, height) = (game.board.width, game.board.height);
+            let start = RaceStart { width, height, difficulty, seed };
+
+            if self.race != Some(start) {
+                self.race = Some(start);
+                self.broadcast(&Message::Start(start));
+            }
+        }
+
+        let race = self.race?;
+
+        if game.seed != Some(race.seed) {
+            return (self.role != Role::Host).then_some(race);
+        }
+
+        if self.opponent.as_ref().map(|opponent| opponent.seed) != Some(race.seed) {
+            self.opponent = Some(RemoteBoard {
+                seed: race.seed,
+                board: game.start_board(),
+                moves: 0,
+                time: Duration::ZERO,
+            });
+            self.host_board = None;
+            self.first_won = None;
+        }
+
+        if let Some(opponent) = self.progress.take().and_then(|progress| remote_board(progress, race, game)) {
+            if opponent.board.solved {
+                self.first_won.get_or_insert(false);
+            }
+
+            self.opponent = Some(opponent);
+        }
+
+        if let Some(host_board) = self.host_progress.take().and_then(|progress| remote_board(progress, race, game)) {
+            self.host_board = Some(host_board);
+        }
+
+        // spectators only watch, they have no board of their own to send
+        let sent_cells = self.sent.as_ref().filter(|sent| sent.seed == race.seed).map(|sent| &sent.cells);
+
+        if self.role != Role::Spectator && sent_cells != Some(&game.board.cells) {
+            let progress = Progress {
+                seed: race.seed,
+                cells: game.board.cells.clone(),
+                moves: game.move_count,
+                time_ms: game.timer.elapsed().as_millis() as u64,
+                host: self.role == Role::Host,
+            };
+            self.broadcast(&Message::Progress(progress.clone()));
+            self.sent = Some(progress);
+        }
+
+        None
+    }
+
+    // games connecting to the host say whether they race or watch first
+    fn accept(&mut self) {
+        if let Some(incoming) = &self.incoming {
+            while let Ok(stream) = incoming.try_recv() {
+                self.greeting.extend(Connection::open(stream));
+            }
+        }
+
+        for connection in std::mem::take(&mut self.greeting) {
+            match connection.receiver.try_recv() {
+                Ok(Message::Hello { watch: true }) => self.add_spectator(connection.stream),
+                // there's only room for one other player, someone else can join once they left
+                Ok(Message::Hello { watch: false }) if !matches!(self.link, Link::Connected(_)) => {
+                    self.link = Link::Connected(connection);
+                    // the new guest has to be told about the race
+                    self.race = None;
+                    self.sent = None;
+                }
+                Err(TryRecvError::Empty) => self.greeting.push(connection),
+                Ok(_) | Err(TryRecvError::Disconnected) => {}
+            }
+        }
+    }
+
+    fn add_spectator(&mut self, mut stream: TcpStream) {
+        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+        let race = self.race.map(Message::Start);
+        let boards = [&self.sent, &self.relayed].map(|progress| progress.clone().map(Message::Progress));
+
+        // the spectator gets the race so far, so they don't have to wait for the next moves
+        let caught_up = race.iter().chain(boards.iter().flatten()).all(|message| write(&mut stream, message));
+
+        if caught_up {
+            self.spectators.push(stream);
+        }
+    }
+
+    fn receive(&mut self) {
+        let connection = match &self.link {
+            Link::Connected(connection) => connection,
+            _ => return,
+        };
+
+        let mut relay = Vec::new();
+
+        loop {
+            match connection.receiver.try_recv() {
+                // only the host starts races
+                Ok(Message::Start(start)) if self.role != Role::Host => self.race = Some(start),
+                Ok(Message::Progress(progress)) if self.role == Role::Host => {
+                    relay.push(progress.clone());
+                    self.progress = Some(progress);
+                }
+                // a spectator's own board shows the host's, the other one the guest's
+                Ok(Message::Progress(progress)) if self.role == Role::Spectator && progress.host => {
+                    self.host_progress = Some(progress)
+                }
+                Ok(Message::Progress(progress)) => self.progress = Some(progress),
+                Ok(Message::Start(_) | Message::Hello { .. }) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.link = Link::Closed;
+                    break;
+                }
+            }
+        }
+
+        // the host passes the guest's board on to the spectators
+        for progress in relay {
+            let message = Message::Progress(progress.clone());
+            self.spectators.retain_mut(|stream| write(stream, &message));
+            self.relayed = Some(progress);
+        }
+    }
+
+    fn send(&mut self, message: &Message) {
+        if let Link::Connected(connection) = &mut self.link {
+            if !write(&mut connection.stream, message) {
+                self.link = Link::Closed;
+            }
+        }
+    }
+
+    // to the other player and everyone watching
+    fn broadcast(&mut self, message: &Message) {
+        self.send(message);
+        self.spectators.retain_mut(|stream| write(stream, message));
+    }
+
+    // whether the board can be played, the race waits until both players are there. once the
+    // other player left it goes on alone. spectators never play
+    pub fn is_ready(&self) -> bool {
+        match (self.role, &self.link) {
+            (Role::Spectator, _) | (_, Link::Waiting) => false,
+            (_, Link::Connected(_)) => self.race.is_some(),
+            (_, Link::Closed) => true,
+        }
+    }
+
+    // the guest and spectators don't start boards of their own while the host is there to start them
+    pub fn follows_host(&self) -> bool {
+        self.role != Role::Host && matches!(self.link, Link::Connected(_))
+    }
+
+    // what the race is waiting for before it can start
+    pub fn waiting_for(&self) -> Option<&str> {
+        match self.link {
+            Link::Waiting => Some("Waiting for someone to join..."),
+            Link::Connected(_) if self.race.is_none() => Some("Waiting for the host..."),
+            Link::Connected(_) | Link::Closed => None,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self.link, Link::Closed)
+    }
+
+    pub fn opponent(&self) -> Option<&RemoteBoard> {
+        self.opponent.as_ref()
+    }
+
+    // the host's board as a spectator sees it
+    pub fn host_board(&self) -> Option<&RemoteBoard> {
+        self.host_board.as_ref()
+    }
+
+    // this player solved their board, they won unless the other one was done already
+    pub fn solved(&mut self) {
+        self.first_won.get_or_insert(true);
+    }
+
+    pub fn first_won(&self) -> Option<bool> {
+        self.first_won
+    }
+}
+
+impl Connection {
+    // reads the messages from the other end on a thread of their own. lines that aren't a message
+    // are skipped, the receiver is disconnected once the connection closes
+    fn open(stream: TcpStream) -> Option<Connection> {
+        let reader = stream.try_clone().ok()?;
+        // moves are small and should arrive right away
+        let _ = stream.set_nodelay(true);
+        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+
+                if let Ok(message) = serde_json::from_str(&line) {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Some(Connection { stream, receiver })
+    }
+}
+
+// false once the connection is gone
+fn write(stream: &mut TcpStream, message: &Message) -> bool {
+    let line = serde_json::to_string(message).unwrap_or_default() + "\n";
+    stream.write_all(line.as_bytes()).is_ok()
+}
+
+// the board a player's progress left, None if it is from another race or not a position of its board
+fn remote_board(progress: Progress, race: RaceStart, game: &Game) -> Option<RemoteBoard> {
+    if progress.seed != race.seed {
+        return None;
+    }
+
+    let mut board = game.start_board();
+    board.set_cells(progress.cells).ok()?;
+
+    Some(RemoteBoard {
+        seed: progress.seed,
+        board,
+        moves: progress.moves,
+        time: Duration::from_millis(progress.time_ms),
+    })
+}
@@ -0,0 +1,98 @@
+use crate::Direction;
+
+// moves are written as the direction the tiles slide in, which is the opposite of where the empty
+// cell goes: U, D, L or R, followed by a count when the same move is repeated, e.g. "U2 L D3"
+
+fn letter(direction: Direction) -> char {
+    match direction.opposite() {
+        Direction::Up => 'U',
+        Direction::Down => 'D',
+        Direction::Left => 'L',
+        Direction::Right => 'R',
+    }
+}
+
+// takes moves of the empty cell, like the game's history
+pub fn format(moves: &[Direction]) -> String {
+    let mut groups: Vec<(Direction, usize)> = Vec::new();
+
+    for direction in moves {
+        match groups.last_mut() {
+            Some((last, count)) if last == direction => *count += 1,
+            _ => groups.push((*direction, 1)),
+        }
+    }
+
+    groups
+        .iter()
+        .map(|(direction, count)| match count {
+            1 => letter(*direction).to_string(),
+            _ => letter(*direction).to_string() + &count.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// returns moves of the empty cell. spaces and commas between moves are optional
+pub fn parse(text: &str) -> Result<Vec<Direction>, String> {
+    let mut moves = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let tile_direction = match c.to_ascii_uppercase() {
+            'U' => Direction::Up,
+            'D' => Direction::Down,
+            'L' => Direction::Left,
+            'R' => Direction::Right,
+            ' ' | '\t' | '\n' | ',' => continue,
+            _ => return Err(format!("unexpected '{}' in the moves", c)),
+        };
+
+        let mut digits = String::new();
+
+        while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+            digits.push(digit);
+        }
+
+        let count = match digits.as_str() {
+            "" => 1,
+            _ => digits.parse().map_err(|_| format!("invalid move count {}", digits))?,
+        };
+
+        // nobody needs more than this in one go, and it keeps typos from eating all the memory
+        if count == 0 || count > 1000 {
+            return Err(format!("invalid move count {}", digits));
+        }
+
+        moves.extend(std::iter::repeat_n(tile_direction.opposite(), count));
+    }
+
+    Ok(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_groups_repeated_moves() {
+        let moves = [Direction::Down, Direction::Down, Direction::Right, Direction::Up, Direction::Up, Direction::Up];
+
+        assert!(format(&moves) == "U2 L D3");
+    }
+
+    #[test]
+    fn parse_round_trips_through_format() {
+        for text in ["U2 L D3", "R", "L10 U R2 D", ""] {
+            assert!(format(&parse(text).unwrap()) == text, "{}", text);
+        }
+
+        assert!(format(&parse("u2,l d3").unwrap()) == "U2 L D3");
+        assert!(format(&parse("UUL").unwrap()) == "U2 L");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_moves() {
+        assert!(parse("U2 X").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::replay::Replay;
+use crate::Game;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+// the server is set with online_leaderboard_url in the config. solves are posted as json to
+// <url>/solves, and GET <url>/top?width=4&height=4 returns the fastest solves of a size as a json list.
+// requests run on their own thread and errors are ignored, it is fine to play offline. the browser build
+// has no threads to wait for the server on, so it plays offline
+
+#[cfg(not(target_arch = "wasm32"))]
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+pub struct Submission {
+    width: u8,
+    height: u8,
+    seed: Option<u64>,
+    time_ms: u64,
+    moves: u32,
+    // lets the server tell solves apart, and ask for the replay later on
+    replay_hash: String,
+}
+
+impl Submission {
+    pub fn new(game: &Game, replay: &Replay) -> Submission {
+        Submission {
+            width: game.board.width,
+            height: game.board.height,
+            seed: game.seed,
+            time_ms: game.timer.elapsed().as_millis() as u64,
+            moves: game.move_count,
+            replay_hash: format!("{:016x}", replay_hash(replay)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Entry {
+    pub time_ms: u64,
+    pub moves: u32,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+// 64 bit FNV-1a of the replay's json, it has to stay the same across platforms and rust versions
+fn replay_hash(replay: &Replay) -> u64 {
+    let json = serde_json::to_string(replay).unwrap_or_default();
+
+    json.bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn submit(url: &str, submission: Submission) {
+    let url = url.trim_end_matches('/').to_owned() + "/solves";
+
+    thread::spawn(move || {
+        let _ = ureq::post(&url).timeout(TIMEOUT).send_json(submission);
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn submit(_: &str, _: Submission) {}
+
+// the global top times of a board size, fetched in the background
+pub struct TopTimes {
+    receiver: Receiver<Option<Vec<Entry>>>,
+    // None until the server answered
+    result: Option<Option<Vec<Entry>>>,
+}
+
+impl TopTimes {
+    pub fn fetch(url: &str, width: u8, height: u8) -> TopTimes {
+        let (sender, receiver) = mpsc::channel();
+        request_top_times(url.trim_end_matches('/').to_owned() + "/top", width, height, sender);
+
+        TopTimes {
+            receiver,
+            result: None,
+        }
+    }
+
+    // None while waiting for the server, then the entries or None if it could not be reached
+    pub fn poll(&mut self) -> Option<Option<&[Entry]>> {
+        if self.result.is_none() {
+            match self.receiver.try_recv() {
+                Ok(entries) => self.result = Some(entries),
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.result = Some(None),
+            }
+        }
+
+        self.result.as_ref().map(|entries| entries.as_deref())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn request_top_times(url: String, width: u8, height: u8, sender: Sender<Option<Vec<Entry>>>) {
+    thread::spawn(move || {
+        let entries = ureq::get(&url)
+            .query("width", &width.to_string())
+            .query("height", &height.to_string())
+            .timeout(TIMEOUT)
+            .call()
+            .ok()
+            .and_then(|response| response.into_json().ok());
+        let _ = sender.send(entries);
+    });
+}
+
+// the sender is dropped right away, so the server shows up as unreachable
+#[cfg(target_arch = "wasm32")]
+fn request_top_times(_: String, _: u8, _: u8, _: Sender<Option<Vec<Entry>>>) {}
@@ -0,0 +1,58 @@
+use raylib::prelude::*;
+use std::path::Path;
+
+// a picture cut into the tiles of the board, for image puzzles. each tile shows the part of the
+// picture that belongs where the tile goes on the solved board
+pub struct Picture {
+    texture: Texture2D,
+}
+
+impl Picture {
+    pub fn load(rl: &mut RaylibHandle, thread: &RaylibThread, path: &Path) -> Result<Picture, String> {
+        let mut texture = rl.load_texture(thread, &path.to_string_lossy())?;
+        texture.set_texture_filter(thread, TextureFilter::TEXTURE_FILTER_BILINEAR);
+        Ok(Picture { texture })
+    }
+
+    // the part of the picture the board shows. pictures that are wider or taller than the board
+    // are cut down to its shape, keeping the middle
+    fn crop(&self, width: u8, height: u8) -> Rectangle {
+        let (picture_width, picture_height) = (self.texture.width() as f32, self.texture.height() as f32);
+        let board_aspect = width as f32 / height as f32;
+
+        if picture_width / picture_height > board_aspect {
+            let cropped_width = picture_height * board_aspect;
+            Rectangle::new((picture_width - cropped_width) / 2.0, 0.0, cropped_width, picture_height)
+        } else {
+            let cropped_height = picture_width / board_aspect;
+            Rectangle::new(0.0, (picture_height - cropped_height) / 2.0, picture_width, cropped_height)
+        }
+    }
+
+    // the whole picture as the solved board shows it
+    pub fn draw(&self, d: &mut RaylibDrawHandle, width: u8, height: u8, bounds: Rectangle, tint: Color) {
+        d.draw_texture_pro(&self.texture, self.crop(width, height), bounds, Vector2::zero(), 0.0, tint);
+    }
+
+    pub fn draw_tile(
+        &self,
+        d: &mut RaylibDrawHandle,
+        tile: i32,
+        width: u8,
+        height: u8,
+        bounds: Rectangle,
+        tint: Color,
+    ) {
+        let crop = self.crop(width, height);
+        let (tile_width, tile_height) = (crop.width / width as f32, crop.height / height as f32);
+        let index = tile as usize - 1;
+        let source = Rectangle::new(
+            crop.x + (index % width as usize) as f32 * tile_width,
+            crop.y + (index / width as usize) as f32 * tile_height,
+            tile_width,
+            tile_height,
+        );
+
+        d.draw_texture_pro(&self.texture, source, bounds, Vector2::zero(), 0.0, tint);
+    }
+}
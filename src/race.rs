@@ -0,0 +1,78 @@
+use crate::solver::{SolverHeuristic, SolverJob};
+use crate::{Board, Direction};
+use std::time::Duration;
+
+// the computer's side of a race, it solves the same scramble on its own board. its moves follow a
+// fixed schedule from the start of the player's timer, so both sides get the same clock
+pub struct Race {
+    pub board: Board,
+    start: Board,
+    job: Option<SolverJob>,
+    solution: Option<Vec<Direction>>,
+    moves_played: usize,
+    moves_per_second: f32,
+}
+
+impl Race {
+    pub fn new(start: &Board, heuristic: SolverHeuristic, max_nodes: u64, moves_per_second: f32) -> Race {
+        Race {
+            board: start.clone(),
+            start: start.clone(),
+            job: Some(SolverJob::start(start, heuristic, max_nodes)),
+            solution: None,
+            moves_played: 0,
+            // a rate of 0 would never finish, which is no race at all
+            moves_per_second: moves_per_second.max(0.1),
+        }
+    }
+
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    // true while the computer is still looking for a solution
+    pub fn is_thinking(&self) -> bool {
+        self.job.is_some()
+    }
+
+    // plays every move of the computer that is due by the given time into the race
+    pub fn update(&mut self, time: Duration) {
+        if let Some(result) = self.job.as_ref().and_then(|job| job.poll()) {
+            self.solution = result;
+            self.job = None;
+        }
+
+        let solution = match &self.solution {
+            Some(solution) => solution,
+            None => return,
+        };
+
+        let due = ((time.as_secs_f32() * self.moves_per_second) as usize).min(solution.len());
+
+        for direction in &solution[self.moves_played..due] {
+            self.board.move_empty(*direction);
+        }
+
+        self.moves_played = self.moves_played.max(due);
+    }
+
+    // when the computer solves its board, None if it has no solution (yet)
+    pub fn finish_time(&self) -> Option<Duration> {
+        let solution = self.solution.as_ref()?;
+        Some(Duration::from_secs_f32(solution.len() as f32 / self.moves_per_second))
+    }
+
+    // whether a solve taking the given time beats the computer
+    pub fn is_beaten_by(&self, time: Duration) -> bool {
+        self.finish_time().is_none_or(|finish_time| time < finish_time)
+    }
+}
+
+impl Drop for Race {
+    // nobody needs the solution of a scramble that was left
+    fn drop(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancel();
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::font;
+use crate::picture::Picture;
+use raylib::prelude::*;
+
+// what the board and the screens around it are drawn with, so they aren't tied to raylib and other
+// backends (or one recording what was drawn) can be put in its place. the menus, the settings and
+// the editor still draw with raylib's draw handle
+pub trait Renderer {
+    // the backend's picture for image puzzles
+    type Picture;
+
+    // a tile of the board. image puzzles show the tile's part of the picture, tinted with the color,
+    // otherwise the tile is filled with it. size is the width and height of the board
+    fn draw_tile(
+        &mut self,
+        bounds: impl Into<Rect>,
+        tile: i32,
+        size: (u8, u8),
+        picture: Option<&Self::Picture>,
+        color: impl Into<Rgba>,
+    );
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, size: i32, color: impl Into<Rgba>);
+
+    fn measure_text(&self, text: &str, size: i32) -> i32;
+
+    // a filled rectangle, e.g. a cell or the background of a label
+    fn draw_panel(&mut self, bounds: impl Into<Rect>, color: impl Into<Rgba>);
+
+    // the border of a rectangle, drawn inside of it
+    fn draw_outline(&mut self, bounds: impl Into<Rect>, thickness: f32, color: impl Into<Rgba>);
+
+    // a straight line, e.g. for the strokes of the correctness symbols
+    fn draw_segment(&mut self, start: (f32, f32), end: (f32, f32), thickness: f32, color: impl Into<Rgba>);
+
+    // the width and height of what is drawn on, in pixels
+    fn screen_size(&self) -> (i32, i32);
+}
+
+// in pixels, from the top left corner
+#[derive(Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect { x, y, width, height }
+    }
+}
+
+// x, y, width and height in whole pixels
+impl From<(i32, i32, i32, i32)> for Rect {
+    fn from((x, y, width, height): (i32, i32, i32, i32)) -> Rect {
+        Rect::new(x as f32, y as f32, width as f32, height as f32)
+    }
+}
+
+// a color, each of red, green, blue and alpha from 0 to 255
+#[derive(Clone, Copy, PartialEq)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+// the raylib backend
+
+impl From<Rectangle> for Rect {
+    fn from(rectangle: Rectangle) -> Rect {
+        Rect::new(rectangle.x, rectangle.y, rectangle.width, rectangle.height)
+    }
+}
+
+impl From<Rect> for Rectangle {
+    fn from(rect: Rect) -> Rectangle {
+        Rectangle::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+impl From<Color> for Rgba {
+    fn from(color: Color) -> Rgba {
+        Rgba {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        }
+    }
+}
+
+impl From<Rgba> for Color {
+    fn from(color: Rgba) -> Color {
+        Color::new(color.r, color.g, color.b, color.a)
+    }
+}
+
+impl Renderer for RaylibDrawHandle<'_> {
+    type Picture = Picture;
+
+    fn draw_tile(
+        &mut self,
+        bounds: impl Into<Rect>,
+        tile: i32,
+        size: (u8, u8),
+        picture: Option<&Picture>,
+        color: impl Into<Rgba>,
+    ) {
+        let (bounds, color) = (Rectangle::from(bounds.into()), Color::from(color.into()));
+
+        match picture {
+            Some(picture) => picture.draw_tile(self, tile, size.0, size.1, bounds, color),
+            None => self.draw_rectangle_rec(bounds, color),
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, size: i32, color: impl Into<Rgba>) {
+        font::draw_text(self, text, x, y, size, Color::from(color.into()));
+    }
+
+    fn measure_text(&self, text: &str, size: i32) -> i32 {
+        font::measure_text(text, size)
+    }
+
+    fn draw_panel(&mut self, bounds: impl Into<Rect>, color: impl Into<Rgba>) {
+        self.draw_rectangle_rec(Rectangle::from(bounds.into()), Color::from(color.into()));
+    }
+
+    fn draw_outline(&mut self, bounds: impl Into<Rect>, thickness: f32, color: impl Into<Rgba>) {
+        self.draw_rectangle_lines_ex(Rectangle::from(bounds.into()), thickness, Color::from(color.into()));
+    }
+
+    fn draw_segment(&mut self, start: (f32, f32), end: (f32, f32), thickness: f32, color: impl Into<Rgba>) {
+        let (start, end) = (Vector2::new(start.0, start.1), Vector2::new(end.0, end.1));
+        self.draw_line_ex(start, end, thickness, Color::from(color.into()));
+    }
+
+    fn screen_size(&self) -> (i32, i32) {
+        (self.get_screen_width(), self.get_screen_height())
+    }
+}
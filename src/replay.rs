@@ -0,0 +1,237 @@
+use crate::events::{Event, EventBus, Subscriber};
+use crate::online;
+use crate::solver::manhattan_distance;
+use crate::storage;
+use crate::{Board, Direction, Game};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const MIN_SPEED: f32 = 0.25;
+pub const MAX_SPEED: f32 = 16.0;
+
+// a move of the empty cell, timed from the start of the solve
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ReplayMove {
+    pub direction: Direction,
+    pub time_ms: u64,
+}
+
+// every move of a solve, undos included, so it can be watched again
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub start: Board,
+    pub seed: Option<u64>,
+    pub moves: Vec<ReplayMove>,
+}
+
+impl Replay {
+    // None if the recorded moves don't lead to the current board, e.g. for games saved before
+    // replays were recorded
+    pub fn new(game: &Game) -> Option<Replay> {
+        let replay = Replay {
+            start: game.start_board(),
+            seed: game.seed,
+            moves: game.replay_moves.clone(),
+        };
+
+        (replay.end_board()?.cells == game.board.cells).then_some(replay)
+    }
+
+    // the time of the last move
+    pub fn duration(&self) -> Duration {
+        self.moves
+            .last()
+            .map_or(Duration::ZERO, |last_move| Duration::from_millis(last_move.time_ms))
+    }
+
+    // the board after every move, None if a move leaves the board
+    fn end_board(&self) -> Option<Board> {
+        let mut board = self.start.clone();
+
+        for replay_move in &self.moves {
+            if !board.move_empty(replay_move.direction) {
+                return None;
+            }
+        }
+
+        Some(board)
+    }
+}
+
+// e.g. ~/.local/share/fifteen-puzzle/replays on linux
+pub fn replay_dir() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("replays"))
+}
+
+// saves to a new file in the replay directory and returns its path
+pub fn save(replay: &Replay) -> Result<PathBuf, String> {
+    let dir = replay_dir().ok_or("no data directory on this platform")?;
+    let date = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!(
+        "{}x{}-{}.json",
+        replay.start.width, replay.start.height, date
+    ));
+    let json = serde_json::to_string(replay).map_err(|error| error.to_string())?;
+
+    fs::create_dir_all(&dir).map_err(|error| format!("could not create {}: {}", dir.display(), error))?;
+    fs::write(&path, json).map_err(|error| format!("could not write {}: {}", path.display(), error))?;
+    Ok(path)
+}
+
+pub fn load(path: &Path) -> Result<Replay, String> {
+    let json = fs::read_to_string(path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    let mut replay: Replay = serde_json::from_str(&json)
+        .map_err(|error| format!("invalid replay {}: {}", path.display(), error))?;
+
+    if !replay.start.is_valid() || replay.end_board().is_none() {
+        return Err(format!("invalid replay {}: the moves don't fit the board", path.display()));
+    }
+
+    replay.start.check_solved();
+    Ok(replay)
+}
+
+// saves the replay of every solve that counts towards the stats, and submits it to the online
+// leaderboard when there is one
+pub struct Recorder {
+    leaderboard_url: Option<String>,
+}
+
+impl Recorder {
+    pub fn new(leaderboard_url: Option<String>) -> Recorder {
+        Recorder { leaderboard_url }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        if !matches!(event, Event::MoveApplied { solved: true, .. }) || !game.counts_for_stats() {
+            return;
+        }
+
+        let replay = match Replay::new(game) {
+            Some(replay) => replay,
+            None => return,
+        };
+
+        match save(&replay) {
+            Ok(path) => println!("saved replay to {}", path.display()),
+            Err(error) => eprintln!("could not save replay: {}", error),
+        }
+
+        if let Some(url) = &self.leaderboard_url {
+            online::submit(url, online::Submission::new(game, &replay));
+        }
+    }
+}
+
+// the fastest saved solve of the same start position, if there is one
+pub fn find_best(start: &Board) -> Option<Replay> {
+    let entries = fs::read_dir(replay_dir()?).ok()?;
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| load(&entry.path()).ok())
+        .filter(|replay| replay.start.width == start.width && replay.start.cells == start.cells)
+        .min_by_key(|replay| replay.duration())
+}
+
+// a previous solve to race against, it follows its recorded schedule as the timer runs
+pub struct Ghost {
+    replay: Replay,
+    start_distance: u32,
+}
+
+impl Ghost {
+    pub fn new(replay: Replay) -> Ghost {
+        Ghost {
+            start_distance: manhattan_distance(&replay.start),
+            replay,
+        }
+    }
+
+    // how far along a board is from the start position to solved, from 0 to 1
+    pub fn progress_of(&self, board: &Board) -> f32 {
+        if self.start_distance == 0 {
+            return 1.0;
+        }
+
+        1.0 - manhattan_distance(board) as f32 / self.start_distance as f32
+    }
+
+    // the progress of the ghost at the given time into the solve
+    pub fn progress_at(&self, time: Duration) -> f32 {
+        let mut board = self.replay.start.clone();
+
+        for replay_move in self.replay.moves.iter().take_while(|replay_move| {
+            Duration::from_millis(replay_move.time_ms) <= time
+        }) {
+            board.move_empty(replay_move.direction);
+        }
+
+        self.progress_of(&board)
+    }
+}
+
+// plays the moves of a replay back on their original schedule, sped up or slowed down by speed
+pub struct Playback {
+    replay: Replay,
+    next_move: usize,
+    clock: Duration,
+    pub speed: f32,
+}
+
+impl Playback {
+    pub fn new(replay: Replay, speed: f32) -> Playback {
+        Playback {
+            replay,
+            next_move: 0,
+            clock: Duration::ZERO,
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED),
+        }
+    }
+
+    pub fn start_board(&self) -> Board {
+        self.replay.start.clone()
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.replay.seed
+    }
+
+    // the time into the original solve
+    pub fn clock(&self) -> Duration {
+        self.clock
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_move == self.replay.moves.len()
+    }
+
+    // moves the clock forward by a frame and returns the moves that are due
+    pub fn advance(&mut self, frame_time: Duration) -> Vec<Direction> {
+        self.clock += frame_time.mul_f32(self.speed);
+        let mut moves = Vec::new();
+
+        while let Some(replay_move) = self.replay.moves.get(self.next_move) {
+            if Duration::from_millis(replay_move.time_ms) > self.clock {
+                break;
+            }
+
+            moves.push(replay_move.direction);
+            self.next_move += 1;
+        }
+
+        // the clock stops with the last move, so the final time matches the solve
+        if let Some(last_move) = self.replay.moves.last().filter(|_| self.is_finished()) {
+            self.clock = Duration::from_millis(last_move.time_ms);
+        }
+
+        moves
+    }
+}
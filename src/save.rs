@@ -0,0 +1,95 @@
+use crate::replay::ReplayMove;
+use crate::storage;
+use crate::{Board, Direction, Game, Timer};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_SAVE_PATH: &str = "savegame.json";
+
+// the game is autosaved here on exit, e.g. ~/.local/share/fifteen-puzzle/autosave.json on linux
+pub fn autosave_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("autosave.json"))
+}
+
+// everything needed to continue a game later
+#[derive(Serialize, Deserialize)]
+pub struct SaveState {
+    board: Board,
+    move_count: u32,
+    undo_count: u32,
+    elapsed: Duration,
+    history: Vec<Direction>,
+    // which empty cell made each move of the history, saves from before two blanks only moved the first
+    #[serde(default)]
+    blanks: Option<Vec<bool>>,
+    completed_level_count: i32,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    replay_moves: Vec<ReplayMove>,
+    // the board as it was scrambled, older saves start retries from the board they were saved with
+    #[serde(default)]
+    start: Option<Board>,
+}
+
+impl SaveState {
+    pub fn new(game: &Game, completed_level_count: i32) -> SaveState {
+        SaveState {
+            board: game.board.clone(),
+            move_count: game.move_count,
+            undo_count: game.undo_count,
+            elapsed: game.timer.elapsed(),
+            history: game.history.clone(),
+            blanks: Some(game.blanks.clone()),
+            completed_level_count,
+            seed: game.seed,
+            replay_moves: game.replay_moves.clone(),
+            start: Some(game.start.clone()),
+        }
+    }
+
+    // returns the restored game and the number of completed levels
+    pub fn restore(self) -> (Game, i32) {
+        let mut game = Game::new(self.board);
+        if let Some(start) = self.start {
+            game.start = start;
+        }
+        game.move_count = self.move_count;
+        game.undo_count = self.undo_count;
+        game.timer = Timer::with_elapsed(self.elapsed);
+        game.blanks = self.blanks.unwrap_or_else(|| vec![false; self.history.len()]);
+        game.history = self.history;
+        game.seed = self.seed;
+        game.replay_moves = self.replay_moves;
+
+        (game, self.completed_level_count)
+    }
+}
+
+pub fn save(path: &Path, state: &SaveState) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(state).map_err(|error| error.to_string())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("could not create {}: {}", parent.display(), error))?;
+    }
+
+    storage::write(path, &json).map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
+
+pub fn load(path: &Path) -> Result<SaveState, String> {
+    let json = storage::read_to_string(path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    let mut state: SaveState = serde_json::from_str(&json)
+        .map_err(|error| format!("invalid save file {}: {}", path.display(), error))?;
+
+    if !state.board.is_valid() {
+        return Err(format!(
+            "invalid save file {}: the board is not a valid puzzle",
+            path.display()
+        ));
+    }
+
+    state.board.check_solved();
+    Ok(state)
+}
@@ -0,0 +1,41 @@
+use crate::{daily, storage};
+use raylib::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// e.g. ~/.local/share/fifteen-puzzle/screenshots on linux
+pub fn screenshot_dir() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("screenshots"))
+}
+
+// saves what is on the screen to a new png named after the current utc time down to the
+// millisecond, e.g. screenshot-2024-05-01-13-45-09-250.png, and returns its path
+pub fn save(rl: &RaylibHandle, thread: &RaylibThread) -> Result<PathBuf, String> {
+    let dir = screenshot_dir().ok_or("no data directory on this platform")?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+    let (seconds, millis) = (millis / 1000, millis % 1000);
+    let time_of_day = seconds % 86400;
+    let path = dir.join(format!(
+        "screenshot-{}-{:02}-{:02}-{:02}-{:03}.png",
+        daily::format_date((seconds / 86400) as i64),
+        time_of_day / 3600,
+        time_of_day / 60 % 60,
+        time_of_day % 60,
+        millis
+    ));
+
+    fs::create_dir_all(&dir).map_err(|error| format!("could not create {}: {}", dir.display(), error))?;
+
+    rl.load_image_from_screen(thread).export_image(&path.to_string_lossy());
+
+    // raylib only logs it when the image can't be written
+    if !path.exists() {
+        return Err(format!("could not write {}", path.display()));
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,195 @@
+use crate::config::{key_name, Config};
+use crate::input::{Action, Input};
+use crate::{font, Difficulty};
+use raylib::prelude::*;
+
+// the rows above the keybinds, which are changed with the left and right arrows
+const OPTIONS: [&str; 10] = [
+    "Difficulty",
+    "Theme",
+    "Board size",
+    "Animation speed",
+    "Volume",
+    "Correctness colors",
+    "Arrows move",
+    "WASD keys",
+    "Vim keys (hjkl)",
+    "Numpad keys",
+];
+const ROW_HEIGHT: i32 = 24;
+
+// a setting changed on the settings screen. the steps are -1 or 1, for the left or right arrow
+pub enum Change {
+    Difficulty(i32),
+    Theme(i32),
+    BoardSize(i32),
+    AnimationSpeed(i32),
+    Volume(i32),
+    CorrectnessColors(i32),
+    // switches between the arrows moving the empty cell and sliding tiles into it
+    MoveTiles,
+    // turns the wasd, the vim or the numpad movement keys on or off
+    WasdKeys,
+    VimKeys,
+    NumpadKeys,
+    // a new key for the action, added to its keys rather than replacing them if the bool is set
+    Key(Action, KeyboardKey, bool),
+    NewGame,
+}
+
+// the settings the game can be changed with, without editing the config file. every change is
+// saved to it right away
+pub struct Settings {
+    selected: usize,
+    // waiting for the new key of the selected keybind, which is added to its keys if this is set
+    rebinding: Option<bool>,
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings {
+            selected: 0,
+            rebinding: None,
+        }
+    }
+
+    // while a keybind is being changed every key goes to it, rather than to what it is bound to
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding.is_some()
+    }
+
+    pub fn update(&mut self, rl: &RaylibHandle, input: &Input) -> Option<Change> {
+        let keybind = self.selected.checked_sub(OPTIONS.len()).map(|index| Action::ALL[index]);
+
+        if let Some(add) = self.rebinding {
+            // escape keeps the old keys. keys without a name can't be written to the config file, and
+            // shift is held for adding a key
+            for key in input.pressed_keys() {
+                match (keybind, *key) {
+                    (_, KeyboardKey::KEY_ESCAPE) => {
+                        self.rebinding = None;
+                        return None;
+                    }
+                    (Some(action), key) if key_name::name_of(key) != "NULL" && key != KeyboardKey::KEY_LEFT_SHIFT => {
+                        self.rebinding = None;
+                        return Some(Change::Key(action, key, add));
+                    }
+                    _ => {}
+                }
+            }
+
+            return None;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) && self.selected > 0 {
+            self.selected -= 1;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_DOWN) && self.selected < OPTIONS.len() + Action::ALL.len() - 1 {
+            self.selected += 1;
+        }
+
+        let step = match (rl.is_key_pressed(KeyboardKey::KEY_LEFT), rl.is_key_pressed(KeyboardKey::KEY_RIGHT)) {
+            (true, false) => -1,
+            (false, true) => 1,
+            _ => 0,
+        };
+
+        if step != 0 {
+            return match self.selected {
+                0 => Some(Change::Difficulty(step)),
+                1 => Some(Change::Theme(step)),
+                2 => Some(Change::BoardSize(step)),
+                3 => Some(Change::AnimationSpeed(step)),
+                4 => Some(Change::Volume(step)),
+                5 => Some(Change::CorrectnessColors(step)),
+                6 => Some(Change::MoveTiles),
+                7 => Some(Change::WasdKeys),
+                8 => Some(Change::VimKeys),
+                9 => Some(Change::NumpadKeys),
+                _ => None,
+            };
+        }
+
+        if !rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            return None;
+        }
+
+        // with shift held the new key is bound next to the old ones
+        match keybind {
+            Some(_) => {
+                self.rebinding = Some(rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT));
+                None
+            }
+            None => Some(Change::NewGame),
+        }
+    }
+
+    // size is the board size new games are played at, theme the name of the current theme
+    pub fn draw(
+        &self,
+        d: &mut RaylibDrawHandle,
+        difficulty: Difficulty,
+        theme: &str,
+        size: (u8, u8),
+        volume: f32,
+        config: &Config,
+    ) {
+        let colors = &config.colors;
+        let (screen_width, screen_height) = (d.get_screen_width(), d.get_screen_height());
+        d.draw_rectangle(0, 0, screen_width, screen_height, colors.background_lighter);
+        font::draw_text(d, "Settings", 10, 10, 28, colors.text);
+
+        let values: Vec<String> = [
+            difficulty.name().to_owned(),
+            theme.to_owned(),
+            format!("{}x{}", size.0, size.1),
+            format!("{}x", config.animation_speed),
+            format!("{}%", (volume * 100.0).round()),
+            config.correctness_colors.name().to_owned(),
+            if config.move_tiles { "tiles" } else { "empty cell" }.to_owned(),
+            on_off(config.keybinds.wasd).to_owned(),
+            on_off(config.keybinds.vim).to_owned(),
+            on_off(config.keybinds.numpad).to_owned(),
+        ]
+        .into_iter()
+        .chain(Action::ALL.iter().map(|action| config.keybinds.key_names(*action)))
+        .collect();
+
+        // the list scrolls to keep the selected row on screen
+        let visible = ((screen_height - 100) / ROW_HEIGHT).max(1) as usize;
+        let first = (self.selected + 1).saturating_sub(visible);
+        let names = OPTIONS.iter().copied().chain(Action::ALL.iter().map(|action| action.name()));
+
+        for (row, (name, value)) in names.zip(&values).enumerate().skip(first).take(visible) {
+            let y = 56 + (row - first) as i32 * ROW_HEIGHT;
+            let selected = row == self.selected;
+            let marker = if selected { "> " } else { "  " };
+            font::draw_text(d, &(marker.to_owned() + name), 10, y, 20, colors.text);
+
+            let value = match (selected, row < OPTIONS.len()) {
+                (true, _) if self.rebinding.is_some() => "press a key...".to_owned(),
+                (true, true) => "< ".to_owned() + value + " >",
+                _ => value.to_owned(),
+            };
+            font::draw_text(d, &value, 260, y, 20, colors.text);
+        }
+
+        let footer = if self.rebinding.is_some() {
+            "[ESC] keep the old keys".to_owned()
+        } else {
+            "[ENTER] new game / change key  [SHIFT+ENTER] add key  [".to_owned()
+                + config.keybinds.key_name(Action::Settings)
+                + "] close"
+        };
+        font::draw_text(d, &footer, 10, screen_height - 30, 20, colors.text);
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{Board, Game};
+
+// the progress strip has a square for every tenth of the solve
+const STRIP_LENGTH: usize = 10;
+
+// a summary of a solve to paste into chats, e.g.
+//
+//   15 Puzzle #1234 (4×4) — 01:23.45, 142 moves (opt 52)
+//   🟥🟥🟧🟧🟧🟨🟨🟨🟩🟩
+//
+// the number is the seed, so others can play the same board. optimal is the fewest moves with
+// whether it is only a lower bound, it is left out while the solver is still working it out
+pub fn summary(game: &Game, optimal: Option<(u32, bool)>) -> String {
+    let board = &game.board;
+    let tiles = board.cells.iter().filter(|cell| !board.is_empty_value(**cell)).count();
+
+    let mut text = format!("{} Puzzle", tiles);
+
+    if let Some(seed) = game.seed {
+        text += &format!(" #{}", seed);
+    }
+
+    let millis = game.timer.elapsed().as_millis();
+    let time = format!("{:02}:{:02}.{:02}", millis / 60_000, millis / 1000 % 60, millis / 10 % 100);
+    text += &format!(" ({}×{}) — {}, {} moves", board.width, board.height, time, game.move_count);
+
+    match optimal {
+        Some((moves, false)) => text += &format!(" (opt {})", moves),
+        Some((moves, true)) => text += &format!(" (opt ≥{})", moves),
+        None => {}
+    }
+
+    text + "\n" + &progress_strip(game, tiles)
+}
+
+// how many tiles were in place as the solve went on, from red for hardly any to green once solved
+fn progress_strip(game: &Game, tiles: usize) -> String {
+    let mut board = game.start.clone();
+    let mut in_place = vec![tiles_in_place(&board)];
+
+    for (direction, second_blank) in game.history.iter().zip(&game.blanks) {
+        board.second_blank = *second_blank;
+        board.move_empty(*direction);
+        in_place.push(tiles_in_place(&board));
+    }
+
+    (1..=STRIP_LENGTH)
+        .map(|square| in_place[(in_place.len() - 1) * square / STRIP_LENGTH])
+        .map(|count| match count * 3 / tiles.max(1) {
+            0 => '🟥',
+            1 => '🟧',
+            2 => '🟨',
+            _ => '🟩',
+        })
+        .collect()
+}
+
+fn tiles_in_place(board: &Board) -> usize {
+    (board.cells.iter().zip(board.goal_cells()))
+        .filter(|(cell, goal)| !board.is_empty_value(**cell) && **cell == *goal)
+        .count()
+}
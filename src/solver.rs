@@ -0,0 +1,464 @@
+use crate::{Board, Direction};
+use pattern_db::PatternDatabase;
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+use walking_distance::WalkingDistance;
+
+mod pattern_db;
+mod reduction;
+mod walking_distance;
+
+const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+// the result of searching below a cost bound
+enum Step {
+    Found,
+    // the smallest cost that went over the bound, used as the next bound
+    Exceeded(u32),
+    Aborted,
+}
+
+// the heuristic picked in the config. boards it does not work for fall back to the next best one
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SolverHeuristic {
+    Manhattan,
+    WalkingDistance,
+    PatternDatabase,
+}
+
+// the estimate of the moves left, it must never overestimate for the solutions to be the shortest
+enum Heuristic {
+    // with the index every tile belongs at, from Board::goal_indices
+    Manhattan(Vec<usize>),
+    // expands far fewer nodes than manhattan distance, for boards with sides up to 4
+    WalkingDistance(WalkingDistance),
+    // more accurate still, but only for 4x4 boards
+    PatternDatabase(&'static PatternDatabase),
+}
+
+impl Heuristic {
+    // the walking distance and the pattern database are worked out for boards solved row by row,
+    // other goals always use manhattan distance
+    fn for_board(board: &Board, preferred: SolverHeuristic) -> Heuristic {
+        let row_major = board.is_row_major();
+        let pattern_database = match (preferred, board.width, board.height) {
+            (SolverHeuristic::PatternDatabase, 4, 4) if row_major => pattern_db::get(),
+            _ => None,
+        };
+        let fits_walking_distance =
+            row_major && board.width <= walking_distance::MAX_SIDE && board.height <= walking_distance::MAX_SIDE;
+
+        match (preferred, pattern_database) {
+            (_, Some(database)) => Heuristic::PatternDatabase(database),
+            (SolverHeuristic::Manhattan, _) => Heuristic::Manhattan(board.goal_indices()),
+            _ if fits_walking_distance => Heuristic::WalkingDistance(WalkingDistance::new(board.width, board.height)),
+            _ => Heuristic::Manhattan(board.goal_indices()),
+        }
+    }
+}
+
+// shared by the searches running on every thread
+struct Limit<'a> {
+    nodes: AtomicU64,
+    max_nodes: u64,
+    // set once a solution is found, or the node limit was hit
+    stop: AtomicBool,
+    cancelled: &'a AtomicBool,
+}
+
+#[derive(Clone)]
+struct Search<'a> {
+    board: Board,
+    heuristic: &'a Heuristic,
+    empty_index: usize,
+    path: Vec<Direction>,
+    // nodes are added to the shared count in batches, so the threads don't fight over it
+    unreported_nodes: u64,
+    limit: &'a Limit<'a>,
+}
+
+const NODE_BATCH_SIZE: u64 = 1024;
+
+// a solve running on a worker thread, so the game keeps drawing while it searches
+pub struct SolverJob {
+    board: Board,
+    receiver: Receiver<Option<Vec<Direction>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl SolverJob {
+    pub fn start(board: &Board, heuristic: SolverHeuristic, max_nodes: u64) -> SolverJob {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker_board = board.clone();
+        let worker_cancelled = cancelled.clone();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        thread::spawn(move || {
+            // the receiver is gone if the job was dropped, nobody is waiting for the result then
+            let _ = sender.send(solve(&worker_board, heuristic, max_nodes, &worker_cancelled));
+        });
+
+        // the browser build has no threads, the solve holds up the frame it was started in
+        #[cfg(target_arch = "wasm32")]
+        let _ = sender.send(solve(&worker_board, heuristic, max_nodes, &worker_cancelled));
+
+        SolverJob {
+            board: board.clone(),
+            receiver,
+            cancelled,
+        }
+    }
+
+    // the board the job is solving
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    // None while the solver is still running, then the result of solve
+    pub fn poll(&self) -> Option<Option<Vec<Direction>>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+
+    pub fn cancel(self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// finds a shortest solution with IDA*, returned as the moves of the empty cell.
+// max_nodes limits the work so hard boards can't keep a thread busy forever, None is returned once
+// it runs out, when cancelled is set or if the board can't be solved at all
+pub fn solve(
+    board: &Board,
+    heuristic: SolverHeuristic,
+    max_nodes: u64,
+    cancelled: &AtomicBool,
+) -> Option<Vec<Direction>> {
+    // the moves of two empty cells can't be told apart in a solution, the heuristics would
+    // overestimate the distance on a board that wraps around, and the search knows nothing of falls
+    if !board.is_solvable() || board.two_blanks || board.wrap || board.gravity {
+        return None;
+    }
+
+    let board = &board.solvable_twin();
+
+    let heuristic = Heuristic::for_board(board, heuristic);
+    let limit = Limit {
+        nodes: AtomicU64::new(0),
+        max_nodes,
+        stop: AtomicBool::new(false),
+        cancelled,
+    };
+
+    // setting up the heuristic can take a while, e.g. generating the pattern database
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let root = Search {
+        board: board.clone(),
+        heuristic: &heuristic,
+        empty_index: board.get_empty_index(),
+        path: Vec::new(),
+        unreported_nodes: 0,
+        limit: &limit,
+    };
+
+    let root_heuristic = root.estimate();
+    let mut bound = root_heuristic;
+
+    if root_heuristic == 0 {
+        return Some(Vec::new());
+    }
+
+    loop {
+        // every first move is searched on its own thread, one after the other in the browser
+        #[cfg(not(target_arch = "wasm32"))]
+        let first_moves = DIRECTIONS.par_iter();
+        #[cfg(target_arch = "wasm32")]
+        let first_moves = DIRECTIONS.iter();
+
+        let results: Vec<(Step, Vec<Direction>)> = first_moves
+            .filter_map(|direction| {
+                let mut search = root.clone();
+                let heuristic = search.apply(*direction, root_heuristic)?;
+                let step = search.search(1, bound, heuristic);
+                Some((step, search.path))
+            })
+            .collect();
+
+        let mut next_bound = u32::MAX;
+        let mut aborted = false;
+
+        for (step, path) in results {
+            match step {
+                Step::Found => return Some(path),
+                Step::Exceeded(estimate) => next_bound = next_bound.min(estimate),
+                Step::Aborted => aborted = true,
+            }
+        }
+
+        if aborted {
+            return None;
+        }
+
+        bound = next_bound;
+    }
+}
+
+// a solution found right away on any board, but usually far longer than the shortest one
+pub fn solve_quickly(board: &Board) -> Option<Vec<Direction>> {
+    // the reduction moves tiles through every cell, which walls would get in the way of
+    if board.two_blanks || board.wrap || !board.walls.is_empty() || board.gravity {
+        return None;
+    }
+
+    let board = &board.solvable_twin();
+
+    if board.is_row_major() {
+        return reduction::solve(board);
+    }
+
+    if !board.is_solvable() {
+        return None;
+    }
+
+    // the reduction only solves row by row, so the board is solved that way and the goal is reached
+    // by taking back the solution of the goal itself. half of the goals can't be reached from the
+    // usual solved board, two tiles are swapped on both boards then, the moves don't depend on
+    // which tile is which
+    let mut start = Board::new(board.cells.clone(), board.width, board.height);
+    let mut goal = Board::new(board.goal_cells(), board.width, board.height);
+
+    if !start.is_solvable() {
+        for cells in [&mut start.cells, &mut goal.cells] {
+            let first = cells.iter().position(|cell| *cell == 1).unwrap();
+            let second = cells.iter().position(|cell| *cell == 2).unwrap();
+            cells.swap(first, second);
+        }
+    }
+
+    let to_row_major = reduction::solve(&start)?;
+    let from_goal = reduction::solve(&goal)?;
+    Some(to_row_major.into_iter().chain(from_goal.iter().rev().map(|direction| direction.opposite())).collect())
+}
+
+// the sum of the distances of every tile to its solved position. every move changes it by one,
+// so it never overestimates the number of moves left
+pub fn manhattan_distance(board: &Board) -> u32 {
+    goal_distance(board, &board.goal_indices())
+}
+
+fn goal_distance(board: &Board, goal_indices: &[usize]) -> u32 {
+    let empty_value = board.get_empty_value();
+
+    board
+        .cells
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| **cell != empty_value)
+        .map(|(index, cell)| tile_distance(board, goal_indices, *cell, index))
+        .sum()
+}
+
+fn tile_distance(board: &Board, goal_indices: &[usize], cell: i32, index: usize) -> u32 {
+    let width = board.width as usize;
+    let goal_index = goal_indices[cell as usize - 1];
+
+    ((goal_index / width).abs_diff(index / width) + (goal_index % width).abs_diff(index % width)) as u32
+}
+
+impl Search<'_> {
+    fn estimate(&self) -> u32 {
+        match self.heuristic {
+            Heuristic::Manhattan(goal_indices) => goal_distance(&self.board, goal_indices),
+            Heuristic::WalkingDistance(walking_distance) => walking_distance.estimate(&self.board),
+            Heuristic::PatternDatabase(database) => database.estimate(&self.board.cells),
+        }
+    }
+
+    // the part of the estimate that depends on the tile at index, None if the heuristic can't be
+    // split up by tile and has to be estimated from scratch
+    fn tile_cost(&self, tile: i32, index: usize) -> Option<u32> {
+        match self.heuristic {
+            Heuristic::Manhattan(goal_indices) => Some(tile_distance(&self.board, goal_indices, tile, index)),
+            Heuristic::WalkingDistance(_) => None,
+            // the lookup reads the tile's position from the board, which has to be up to date
+            Heuristic::PatternDatabase(database) => Some(database.tile_cost(tile, &self.board.cells)),
+        }
+    }
+
+    // moves the empty cell and returns the new estimate, None if the move is not possible
+    fn apply(&mut self, direction: Direction, heuristic: u32) -> Option<u32> {
+        let empty_index = self.empty_index;
+        let neighbor_index = self.board.get_neighbor_index(empty_index, direction)?;
+
+        // only the cost of the tile that slides into the empty cell changes
+        let tile = self.board.cells[neighbor_index];
+        let cost_before = self.tile_cost(tile, neighbor_index);
+        self.board.cells.swap(empty_index, neighbor_index);
+        self.empty_index = neighbor_index;
+        self.path.push(direction);
+
+        match (cost_before, self.tile_cost(tile, empty_index)) {
+            (Some(cost_before), Some(cost_after)) => Some(heuristic + cost_after - cost_before),
+            _ => Some(self.estimate()),
+        }
+    }
+
+    fn revert(&mut self) {
+        if let Some(direction) = self.path.pop() {
+            let neighbor_index = self.board.get_neighbor_index(self.empty_index, direction.opposite()).unwrap();
+            self.board.cells.swap(self.empty_index, neighbor_index);
+            self.empty_index = neighbor_index;
+        }
+    }
+
+    // returns false once the search should give up
+    fn count_node(&mut self) -> bool {
+        self.unreported_nodes += 1;
+
+        if self.unreported_nodes < NODE_BATCH_SIZE {
+            return true;
+        }
+
+        let nodes = self.limit.nodes.fetch_add(self.unreported_nodes, Ordering::Relaxed) + self.unreported_nodes;
+        self.unreported_nodes = 0;
+
+        if nodes > self.limit.max_nodes {
+            self.limit.stop.store(true, Ordering::Relaxed);
+        }
+
+        !self.limit.stop.load(Ordering::Relaxed) && !self.limit.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn search(&mut self, cost: u32, bound: u32, heuristic: u32) -> Step {
+        let estimate = cost + heuristic;
+
+        if estimate > bound {
+            return Step::Exceeded(estimate);
+        }
+
+        if heuristic == 0 {
+            self.limit.stop.store(true, Ordering::Relaxed);
+            return Step::Found;
+        }
+
+        if !self.count_node() {
+            return Step::Aborted;
+        }
+
+        let mut next_bound = u32::MAX;
+
+        for direction in DIRECTIONS {
+            // stepping straight back can never be part of a shortest solution
+            if self.path.last().map(|last| last.opposite()) == Some(direction) {
+                continue;
+            }
+
+            let next_heuristic = match self.apply(direction, heuristic) {
+                Some(next_heuristic) => next_heuristic,
+                None => continue,
+            };
+
+            match self.search(cost + 1, bound, next_heuristic) {
+                Step::Found => return Step::Found,
+                Step::Aborted => return Step::Aborted,
+                Step::Exceeded(estimate) => next_bound = next_bound.min(estimate),
+            }
+
+            self.revert();
+        }
+
+        Step::Exceeded(next_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+    use std::collections::{HashMap, VecDeque};
+
+    const HEURISTICS: [SolverHeuristic; 3] =
+        [SolverHeuristic::Manhattan, SolverHeuristic::WalkingDistance, SolverHeuristic::PatternDatabase];
+
+    // the distance to the goal found by a plain breadth first search, only usable on small boards
+    fn breadth_first_distance(board: &Board) -> usize {
+        let goal = board.goal_cells();
+        let mut distances = HashMap::from([(board.cells.clone(), 0)]);
+        let mut queue = VecDeque::from([board.clone()]);
+
+        while let Some(board) = queue.pop_front() {
+            let distance = distances[&board.cells];
+
+            if board.cells == goal {
+                return distance;
+            }
+
+            for direction in (0..4).map(Direction::from) {
+                let mut next = board.clone();
+
+                if next.move_empty(direction) && !distances.contains_key(&next.cells) {
+                    distances.insert(next.cells.clone(), distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        panic!("the goal can't be reached from {}", board);
+    }
+
+    fn solve_fully(board: &Board, heuristic: SolverHeuristic) -> Vec<Direction> {
+        solve(board, heuristic, u64::MAX, &AtomicBool::new(false)).unwrap()
+    }
+
+    #[test]
+    fn solutions_are_optimal_on_small_boards() {
+        for seed in 0..10 {
+            let mut board = Board::solved(3, 3);
+            board.scramble(Difficulty::Random, seed).unwrap();
+            let distance = breadth_first_distance(&board);
+
+            for heuristic in HEURISTICS {
+                let solution = solve_fully(&board, heuristic);
+                assert!(solution.len() == distance, "{} in {} instead of {}", board, solution.len(), distance);
+
+                let mut solved = board.clone();
+                assert!(solution.iter().all(|direction| solved.move_empty(*direction)));
+                assert!(solved.cells == board.goal_cells());
+            }
+        }
+    }
+
+    #[test]
+    fn known_positions_are_solved_optimally() {
+        let positions = [("1 2 3 / 4 5 6 / 7 8 _", 0), ("1 2 3 / 4 5 6 / 7 _ 8", 1), ("8 6 7 / 2 5 4 / 3 _ 1", 31)];
+
+        for (position, length) in positions {
+            let board: Board = position.parse().unwrap();
+
+            for heuristic in HEURISTICS {
+                assert!(solve_fully(&board, heuristic).len() == length, "{}", position);
+            }
+        }
+    }
+
+    #[test]
+    fn unsolvable_boards_have_no_solution() {
+        let board: Board = "1 2 3 / 4 5 6 / 8 7 _".parse().unwrap();
+
+        assert!(solve(&board, SolverHeuristic::Manhattan, u64::MAX, &AtomicBool::new(false)).is_none());
+    }
+}
@@ -0,0 +1,233 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// the tiles are split into three groups of 6, 6 and 3. no tile is in two groups and only moves of a
+// group's own tiles are counted for it, so the costs of the groups can be added up
+const GROUPS: [&[i32]; 3] = [&[1, 5, 6, 9, 10, 13], &[7, 8, 11, 12, 14, 15], &[2, 3, 4]];
+const WIDTH: usize = 4;
+const CELL_COUNT: usize = WIDTH * WIDTH;
+// changing the groups or the file layout needs a new file name, so old caches are not misread
+const CACHE_FILE_NAME: &str = "pattern-db-663-v1.bin";
+
+// precomputed solving costs for 4x4 boards
+pub struct PatternDatabase {
+    // for each group, the fewest moves of its tiles needed to bring them home, indexed by the rank
+    // of the group's tile positions
+    tables: Vec<Vec<u8>>,
+}
+
+static DATABASE: OnceLock<Option<PatternDatabase>> = OnceLock::new();
+
+// the shared database, loaded from the cache or generated and cached on first use.
+// None if it could not be generated
+pub fn get() -> Option<&'static PatternDatabase> {
+    DATABASE
+        .get_or_init(|| match PatternDatabase::load_or_generate() {
+            Ok(database) => Some(database),
+            Err(error) => {
+                eprintln!("could not set up the pattern database: {}", error);
+                None
+            }
+        })
+        .as_ref()
+}
+
+// e.g. ~/.cache/fifteen-puzzle/pattern-db-663-v1.bin on linux
+pub fn cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("fifteen-puzzle").join(CACHE_FILE_NAME))
+}
+
+impl PatternDatabase {
+    fn load_or_generate() -> Result<PatternDatabase, String> {
+        let path = cache_path();
+
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            match PatternDatabase::load(path) {
+                Ok(database) => return Ok(database),
+                Err(error) => eprintln!("{}, generating it again", error),
+            }
+        }
+
+        println!("generating the pattern database, this only happens once and can take a while");
+        let database = PatternDatabase::generate();
+
+        if let Some(path) = path {
+            if let Err(error) = database.save(&path) {
+                eprintln!("could not cache the pattern database: {}", error);
+            }
+        }
+
+        Ok(database)
+    }
+
+    pub fn generate() -> PatternDatabase {
+        PatternDatabase {
+            tables: GROUPS.iter().map(|tiles| generate_table(tiles)).collect(),
+        }
+    }
+
+    fn load(path: &PathBuf) -> Result<PatternDatabase, String> {
+        let bytes = fs::read(path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+        let expected_length: usize = GROUPS.iter().map(|tiles| table_size(tiles.len())).sum();
+
+        if bytes.len() != expected_length {
+            return Err(format!("invalid pattern database {}", path.display()));
+        }
+
+        let mut tables = Vec::new();
+        let mut offset = 0;
+
+        for tiles in GROUPS {
+            let size = table_size(tiles.len());
+            tables.push(bytes[offset..offset + size].to_vec());
+            offset += size;
+        }
+
+        Ok(PatternDatabase { tables })
+    }
+
+    fn save(&self, path: &PathBuf) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| format!("could not create {}: {}", parent.display(), error))?;
+        }
+
+        fs::write(path, self.tables.concat()).map_err(|error| format!("could not write {}: {}", path.display(), error))
+    }
+
+    // a lower bound on the moves needed to solve the cells of a 4x4 board
+    pub fn estimate(&self, cells: &[i32]) -> u32 {
+        (0..GROUPS.len()).map(|group| self.group_cost(group, cells)).sum()
+    }
+
+    // the cost of the group the tile belongs to, the only part of the estimate that changes when it moves
+    pub fn tile_cost(&self, tile: i32, cells: &[i32]) -> u32 {
+        match GROUPS.iter().position(|tiles| tiles.contains(&tile)) {
+            Some(group) => self.group_cost(group, cells),
+            None => 0,
+        }
+    }
+
+    fn group_cost(&self, group: usize, cells: &[i32]) -> u32 {
+        let mut positions = [0; 6];
+
+        for (index, cell) in cells.iter().enumerate() {
+            if let Some(i) = GROUPS[group].iter().position(|tile| tile == cell) {
+                positions[i] = index as u8;
+            }
+        }
+
+        self.tables[group][rank(&positions[..GROUPS[group].len()])] as u32
+    }
+}
+
+// the number of ways to place k distinct tiles on the board
+fn table_size(k: usize) -> usize {
+    (CELL_COUNT - k + 1..=CELL_COUNT).product()
+}
+
+// numbers the placements of a group's tiles from 0 to table_size - 1
+fn rank(positions: &[u8]) -> usize {
+    let mut rank = 0;
+
+    for (i, position) in positions.iter().enumerate() {
+        // only count the cells not taken by the earlier tiles
+        let taken = positions[..i].iter().filter(|earlier| *earlier < position).count();
+        rank = rank * (CELL_COUNT - i) + *position as usize - taken;
+    }
+
+    rank
+}
+
+// a search state is packed into a u32 as 4 bits for each tile position, followed by the empty cell
+fn pack(positions: &[u8], empty_index: u8) -> u32 {
+    positions
+        .iter()
+        .fold(empty_index as u32, |state, position| state << 4 | *position as u32)
+}
+
+fn unpack(state: u32, positions: &mut [u8]) -> u8 {
+    let mut state = state;
+
+    for position in positions.iter_mut().rev() {
+        *position = (state & 0xf) as u8;
+        state >>= 4;
+    }
+
+    state as u8
+}
+
+fn neighbors(index: usize) -> impl Iterator<Item = usize> {
+    let (row, col) = (index / WIDTH, index % WIDTH);
+    let up = (row > 0).then(|| index - WIDTH);
+    let down = (row < WIDTH - 1).then(|| index + WIDTH);
+    let left = (col > 0).then(|| index - 1);
+    let right = (col < WIDTH - 1).then(|| index + 1);
+
+    [up, down, left, right].into_iter().flatten()
+}
+
+// a breadth first search backwards from the solved board, where only moves of the group's tiles
+// cost anything. the other tiles are indistinguishable, so only the empty cell is tracked besides them
+fn generate_table(tiles: &[i32]) -> Vec<u8> {
+    let size = table_size(tiles.len());
+    // indexed by the rank of the tile positions times the cell count plus the empty index
+    let mut costs = vec![u8::MAX; size * CELL_COUNT];
+    let state_index = |positions: &[u8], empty_index: u8| rank(positions) * CELL_COUNT + empty_index as usize;
+
+    let goal: Vec<u8> = tiles.iter().map(|tile| *tile as u8 - 1).collect();
+    let goal_empty_index = CELL_COUNT as u8 - 1;
+    costs[state_index(&goal, goal_empty_index)] = 0;
+
+    let mut layer = vec![pack(&goal, goal_empty_index)];
+    let mut positions = vec![0; tiles.len()];
+    let mut cost = 0;
+
+    while !layer.is_empty() {
+        let mut next_layer = Vec::new();
+
+        // free moves are added to the current layer, moves of the group's tiles to the next one
+        while let Some(state) = layer.pop() {
+            let empty_index = unpack(state, &mut positions);
+
+            if costs[state_index(&positions, empty_index)] != cost {
+                continue;
+            }
+
+            for neighbor_index in neighbors(empty_index as usize) {
+                let neighbor_index = neighbor_index as u8;
+
+                match positions.iter().position(|position| *position == neighbor_index) {
+                    Some(tile) => {
+                        positions[tile] = empty_index;
+                        let index = state_index(&positions, neighbor_index);
+
+                        if costs[index] > cost + 1 {
+                            costs[index] = cost + 1;
+                            next_layer.push(pack(&positions, neighbor_index));
+                        }
+
+                        positions[tile] = neighbor_index;
+                    }
+                    None => {
+                        let index = state_index(&positions, neighbor_index);
+
+                        if costs[index] > cost {
+                            costs[index] = cost;
+                            layer.push(pack(&positions, neighbor_index));
+                        }
+                    }
+                }
+            }
+        }
+
+        layer = next_layer;
+        cost += 1;
+    }
+
+    // where the empty cell ends up does not matter for the estimate, so the cheapest one is kept
+    costs
+        .chunks(CELL_COUNT)
+        .map(|costs| *costs.iter().min().unwrap())
+        .collect()
+}
@@ -0,0 +1,292 @@
+use crate::{Board, Direction};
+use std::collections::VecDeque;
+
+use super::DIRECTIONS;
+
+// solves the board a row or a column at a time, shrinking it until only a 2x2 square is left.
+// the solutions are far from the shortest, but they are found instantly on boards of any size
+pub fn solve(board: &Board) -> Option<Vec<Direction>> {
+    if !board.is_solvable() {
+        return None;
+    }
+
+    let (width, height) = (board.width as usize, board.height as usize);
+    let mut reduction = Reduction {
+        board: board.clone(),
+        locked: vec![false; board.get_cell_count()],
+        moves: Vec::new(),
+    };
+    let (mut top, mut left) = (0, 0);
+
+    // the longer side is shortened first, so the unsolved part stays close to a square
+    while height - top > 2 || width - left > 2 {
+        if height - top >= width - left {
+            reduction.solve_row(top, left)?;
+            top += 1;
+        } else {
+            reduction.solve_column(left, top)?;
+            left += 1;
+        }
+    }
+
+    reduction.solve_square(top, left)?;
+    Some(reduction.moves)
+}
+
+struct Reduction {
+    board: Board,
+    // cells that are solved already, or hold a tile that has to stay put for now
+    locked: Vec<bool>,
+    moves: Vec<Direction>,
+}
+
+impl Reduction {
+    fn index(&self, row: usize, column: usize) -> usize {
+        row * self.board.width as usize + column
+    }
+
+    // the tile that belongs at the index
+    fn goal(&self, index: usize) -> i32 {
+        index as i32 + 1
+    }
+
+    fn position(&self, tile: i32) -> usize {
+        self.board.cells.iter().position(|cell| *cell == tile).unwrap()
+    }
+
+    fn solve_row(&mut self, row: usize, left: usize) -> Option<()> {
+        let width = self.board.width as usize;
+
+        for column in left..width - 2 {
+            let index = self.index(row, column);
+            self.place(self.goal(index), index)?;
+            self.locked[index] = true;
+        }
+
+        // the last two tiles can't be placed one after the other, the first would be in the way of
+        // the second. the second goes where the first belongs with the first below it, then both
+        // are rotated into the row
+        let window = [
+            self.index(row, width - 2),
+            self.index(row, width - 1),
+            self.index(row + 1, width - 2),
+            self.index(row + 1, width - 1),
+            self.index(row + 2, width - 2),
+            self.index(row + 2, width - 1),
+        ];
+        self.finish_line(window)
+    }
+
+    fn solve_column(&mut self, column: usize, top: usize) -> Option<()> {
+        let height = self.board.height as usize;
+
+        for row in top..height - 2 {
+            let index = self.index(row, column);
+            self.place(self.goal(index), index)?;
+            self.locked[index] = true;
+        }
+
+        let window = [
+            self.index(height - 2, column),
+            self.index(height - 1, column),
+            self.index(height - 2, column + 1),
+            self.index(height - 1, column + 1),
+            self.index(height - 2, column + 2),
+            self.index(height - 1, column + 2),
+        ];
+        self.finish_line(window)
+    }
+
+    // places the tiles of the last two cells of a row or column. the window starts with those two
+    // cells, followed by the two cells next to them on the unsolved side and the two after those
+    fn finish_line(&mut self, window: [usize; 6]) -> Option<()> {
+        let (first, second, next_to_first) = (window[0], window[1], window[2]);
+        let (first_tile, second_tile) = (self.goal(first), self.goal(second));
+
+        if self.position(first_tile) != first || self.position(second_tile) != second {
+            self.place(second_tile, first)?;
+            self.locked[first] = true;
+
+            let placed = self.place(first_tile, next_to_first).and_then(|_| {
+                self.locked[next_to_first] = true;
+                self.route_empty(second)
+            });
+
+            self.locked[first] = false;
+            self.locked[next_to_first] = false;
+
+            match placed {
+                // the second tile slides on into its cell, then the first one into the cell it left
+                Some(()) => {
+                    self.move_empty_to(first);
+                    self.move_empty_to(next_to_first);
+                }
+                // the empty cell got stuck in the corner of the line, behind the first tile.
+                // everything is close together then, so the window is searched for a way out
+                None => self.search_window(window, first_tile, second_tile)?,
+            }
+        }
+
+        self.locked[first] = true;
+        self.locked[second] = true;
+        Some(())
+    }
+
+    // moves the two tiles into the first two cells of the window with a breadth first search over
+    // where the two tiles and the empty cell are, the other tiles are all the same to it
+    fn search_window(&mut self, window: [usize; 6], first_tile: i32, second_tile: i32) -> Option<()> {
+        let slot = |index: usize| window.iter().position(|cell| *cell == index);
+        let start = (
+            slot(self.position(first_tile))?,
+            slot(self.position(second_tile))?,
+            slot(self.board.get_empty_index())?,
+        );
+        let key = |(first, second, empty): (usize, usize, usize)| (first * 6 + second) * 6 + empty;
+        let mut previous: Vec<Option<(usize, usize, usize)>> = vec![None; 6 * 6 * 6];
+        let mut queue = VecDeque::from([start]);
+        previous[key(start)] = Some(start);
+
+        while let Some(state) = queue.pop_front() {
+            if state.0 == 0 && state.1 == 1 {
+                let mut empty_path = vec![state.2];
+                let mut step = state;
+
+                while step != start {
+                    step = previous[key(step)]?;
+                    empty_path.push(step.2);
+                }
+
+                for empty_slot in empty_path.into_iter().rev().skip(1) {
+                    self.move_empty_to(window[empty_slot]);
+                }
+
+                return Some(());
+            }
+
+            let (first, second, empty) = state;
+
+            for direction in DIRECTIONS {
+                let neighbor = match self.board.get_neighbor_index(window[empty], direction).and_then(slot) {
+                    Some(neighbor) => neighbor,
+                    None => continue,
+                };
+
+                // the empty cell swaps places with whatever is in the neighboring cell
+                let next = (
+                    if first == neighbor { empty } else { first },
+                    if second == neighbor { empty } else { second },
+                    neighbor,
+                );
+
+                if previous[key(next)].is_none() {
+                    previous[key(next)] = Some(state);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    // cycles the last three tiles around the 2x2 square until they are in place, which takes at
+    // most 12 moves for a solvable board
+    fn solve_square(&mut self, top: usize, left: usize) -> Option<()> {
+        let cycle = [
+            self.index(top, left),
+            self.index(top, left + 1),
+            self.index(top + 1, left + 1),
+            self.index(top + 1, left),
+        ];
+
+        for _ in 0..12 {
+            if cycle.iter().all(|index| self.board.cells[*index] == self.goal(*index)) {
+                return Some(());
+            }
+
+            let empty_index = self.board.get_empty_index();
+            let step = cycle.iter().position(|index| *index == empty_index)?;
+            self.move_empty_to(cycle[(step + 1) % cycle.len()]);
+        }
+
+        cycle
+            .iter()
+            .all(|index| self.board.cells[*index] == self.goal(*index))
+            .then_some(())
+    }
+
+    // moves the tile to the target one cell at a time, going around the locked cells
+    fn place(&mut self, tile: i32, target: usize) -> Option<()> {
+        loop {
+            let position = self.position(tile);
+
+            if position == target {
+                return Some(());
+            }
+
+            let next = self.path(position, target)?[1];
+
+            // the tile has to stay where it is while the empty cell goes around it
+            self.locked[position] = true;
+            let routed = self.route_empty(next);
+            self.locked[position] = false;
+            routed?;
+
+            self.move_empty_to(position);
+        }
+    }
+
+    fn route_empty(&mut self, target: usize) -> Option<()> {
+        let path = self.path(self.board.get_empty_index(), target)?;
+
+        for index in path.into_iter().skip(1) {
+            self.move_empty_to(index);
+        }
+
+        Some(())
+    }
+
+    // a shortest path through unlocked cells, from and to included
+    fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut previous = vec![None; self.locked.len()];
+        let mut queue = VecDeque::from([from]);
+        previous[from] = Some(from);
+
+        while let Some(index) = queue.pop_front() {
+            if index == to {
+                let mut path = vec![to];
+                let mut step = to;
+
+                while step != from {
+                    step = previous[step]?;
+                    path.push(step);
+                }
+
+                path.reverse();
+                return Some(path);
+            }
+
+            for direction in DIRECTIONS {
+                if let Some(neighbor) = self.board.get_neighbor_index(index, direction) {
+                    if !self.locked[neighbor] && previous[neighbor].is_none() {
+                        previous[neighbor] = Some(index);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // the index has to be next to the empty cell
+    fn move_empty_to(&mut self, index: usize) {
+        let empty_index = self.board.get_empty_index();
+        let direction = DIRECTIONS
+            .into_iter()
+            .find(|direction| self.board.get_neighbor_index(empty_index, *direction) == Some(index))
+            .unwrap();
+
+        self.board.cells.swap(empty_index, index);
+        self.moves.push(direction);
+    }
+}
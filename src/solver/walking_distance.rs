@@ -0,0 +1,103 @@
+use crate::Board;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+// bigger boards have far too many walking distance states to generate them on the fly
+pub const MAX_SIDE: u8 = 4;
+
+// counts the moves needed to bring every tile back to its row, ignoring columns, plus the moves
+// needed to bring every tile back to its column. every move is either vertical or horizontal,
+// so the sum never overestimates
+pub struct WalkingDistance {
+    width: usize,
+    height: usize,
+    rows: HashMap<u64, u8>,
+    columns: HashMap<u64, u8>,
+}
+
+impl WalkingDistance {
+    pub fn new(width: u8, height: u8) -> WalkingDistance {
+        WalkingDistance {
+            width: width as usize,
+            height: height as usize,
+            rows: generate_table(height as usize, width as usize),
+            columns: generate_table(width as usize, height as usize),
+        }
+    }
+
+    pub fn estimate(&self, board: &Board) -> u32 {
+        let empty_value = board.get_empty_value();
+        let mut rows = vec![0; self.height * self.height];
+        let mut columns = vec![0; self.width * self.width];
+        let (mut empty_row, mut empty_column) = (0, 0);
+
+        for (index, cell) in board.cells.iter().enumerate() {
+            let (row, column) = (index / self.width, index % self.width);
+
+            if *cell == empty_value {
+                (empty_row, empty_column) = (row, column);
+                continue;
+            }
+
+            let goal_index = *cell as usize - 1;
+            rows[row * self.height + goal_index / self.width] += 1;
+            columns[column * self.width + goal_index % self.width] += 1;
+        }
+
+        let row_cost = self.rows.get(&key(&rows, empty_row)).copied().unwrap_or(0);
+        let column_cost = self.columns.get(&key(&columns, empty_column)).copied().unwrap_or(0);
+        row_cost as u32 + column_cost as u32
+    }
+}
+
+// a line is a row or a column of the board, holding line_length tiles when solved. a state counts
+// for each line how many of its tiles belong in each line, together with the line of the empty cell
+fn generate_table(line_count: usize, line_length: usize) -> HashMap<u64, u8> {
+    let last_line = line_count - 1;
+    let mut goal = vec![0; line_count * line_count];
+
+    for line in 0..line_count {
+        goal[line * line_count + line] = line_length as u8;
+    }
+
+    // the empty cell takes up a spot in the last line
+    goal[last_line * line_count + last_line] -= 1;
+
+    let mut table = HashMap::from([(key(&goal, last_line), 0)]);
+    let mut queue = VecDeque::from([(goal, last_line)]);
+
+    while let Some((counts, empty_line)) = queue.pop_front() {
+        let cost = table[&key(&counts, empty_line)];
+        let neighbor_lines = [empty_line.checked_sub(1), Some(empty_line + 1).filter(|line| *line < line_count)];
+
+        // the empty cell swaps with any tile from a neighboring line, only which line that tile
+        // belongs in matters
+        for neighbor_line in neighbor_lines.into_iter().flatten() {
+            for goal_line in 0..line_count {
+                if counts[neighbor_line * line_count + goal_line] == 0 {
+                    continue;
+                }
+
+                let mut next_counts = counts.clone();
+                next_counts[neighbor_line * line_count + goal_line] -= 1;
+                next_counts[empty_line * line_count + goal_line] += 1;
+
+                let next_key = key(&next_counts, neighbor_line);
+
+                if let Entry::Vacant(entry) = table.entry(next_key) {
+                    entry.insert(cost + 1);
+                    queue.push_back((next_counts, neighbor_line));
+                }
+            }
+        }
+    }
+
+    table
+}
+
+// 3 bits for each count, which is enough for lines of up to 4 tiles
+fn key(counts: &[u8], empty_line: usize) -> u64 {
+    counts
+        .iter()
+        .fold(empty_line as u64, |key, count| key << 3 | *count as u64)
+}
@@ -0,0 +1,138 @@
+use crate::events::{Event, EventBus, Subscriber};
+use crate::storage;
+use crate::{Board, Game};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// a solve is split into phases the way most people go about it: the rows from the top down until two
+// are left, then those two rows column by column from the left, then the last few tiles
+pub struct Phase {
+    pub name: String,
+    // the cells that are in place by the end of the phase, those of the phases before it included
+    cells: Vec<usize>,
+}
+
+impl Phase {
+    pub fn is_done(&self, board: &Board) -> bool {
+        let goal = board.goal_cells();
+        self.cells.iter().all(|index| board.cells[*index] == goal[*index])
+    }
+}
+
+pub fn phases(width: u8, height: u8) -> Vec<Phase> {
+    let (width, height) = (width as usize, height as usize);
+    let top = (height - 2) * width;
+    let mut phases = Vec::new();
+
+    for row in 0..height - 2 {
+        let cells = (0..(row + 1) * width).collect();
+        phases.push(Phase { name: "Row ".to_owned() + &(row + 1).to_string(), cells });
+    }
+
+    for column in 0..width - 2 {
+        let bottom = (height - 2..height).flat_map(|row| (0..=column).map(move |column| row * width + column));
+        let cells = (0..top).chain(bottom).collect();
+        phases.push(Phase { name: "Column ".to_owned() + &(column + 1).to_string(), cells });
+    }
+
+    phases.push(Phase { name: "Solved".to_owned(), cells: (0..width * height).collect() });
+    phases
+}
+
+// how far a split is ahead (-) or behind (+) the best one, in seconds, e.g. -1.250
+pub fn format_delta(time: Duration, best: Duration) -> String {
+    let (sign, delta) = if time < best { ("-", best - time) } else { ("+", time - best) };
+    format!("{}{}.{:03}", sign, delta.as_secs(), delta.subsec_millis())
+}
+
+// the best split of every phase by board size, e.g. "4x4", each in milliseconds. a best split can
+// come from any solve, they aren't the splits of the fastest solve
+#[derive(Default, Serialize, Deserialize)]
+pub struct BestSplits {
+    sizes: HashMap<String, Vec<u64>>,
+    // the best splits from before the last solve improved on them, its deltas are against those
+    #[serde(skip)]
+    before_solve: Option<Vec<u64>>,
+}
+
+impl BestSplits {
+    // what the splits of the game are compared to
+    pub fn compared_to(&self, game: &Game) -> &[u64] {
+        match &self.before_solve {
+            Some(before_solve) if game.board.solved => before_solve,
+            _ => self.sizes.get(&size_key(&game.board)).map_or(&[], |best| best),
+        }
+    }
+}
+
+impl Subscriber for BestSplits {
+    // only solves that count towards the stats can set a best split
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        if event == Event::ScrambleStarted {
+            self.before_solve = None;
+        }
+
+        if !matches!(event, Event::BoardSolved { .. }) || !game.counts_for_stats() {
+            return;
+        }
+
+        let best = self.sizes.entry(size_key(&game.board)).or_default();
+        self.before_solve = Some(best.clone());
+
+        for (index, split) in game.splits.iter().enumerate() {
+            let split = split.as_millis() as u64;
+
+            match best.get_mut(index) {
+                Some(best) => *best = split.min(*best),
+                None => best.push(split),
+            }
+        }
+
+        if let Err(error) = save(self) {
+            eprintln!("{}", error);
+        }
+    }
+}
+
+fn size_key(board: &Board) -> String {
+    board.width.to_string() + "x" + &board.height.to_string()
+}
+
+// e.g. ~/.local/share/fifteen-puzzle/splits.json on linux
+pub fn record_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("splits.json"))
+}
+
+// a missing record means there were no splits yet
+pub fn load() -> BestSplits {
+    let path = match record_path().filter(|path| storage::exists(path)) {
+        Some(path) => path,
+        None => return BestSplits::default(),
+    };
+
+    let result = storage::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|error| error.to_string()));
+
+    match result {
+        Ok(record) => record,
+        Err(error) => {
+            eprintln!("invalid splits record {}: {}", path.display(), error);
+            BestSplits::default()
+        }
+    }
+}
+
+pub fn save(record: &BestSplits) -> Result<(), String> {
+    let path = record_path().ok_or("no data directory on this platform")?;
+    let json = serde_json::to_string_pretty(record).map_err(|error| error.to_string())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("could not create {}: {}", parent.display(), error))?;
+    }
+
+    storage::write(&path, &json).map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
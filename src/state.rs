@@ -0,0 +1,61 @@
+use crate::animation::ScrambleAnimation;
+use crate::{editor, load_statistics, menu, stats, Board};
+
+// what the game is doing, which decides how input is handled and what is drawn over the board.
+// the settings, the audio settings and the leaderboard open over any of these
+pub enum GameState {
+    // the title screen the game starts on
+    Menu(menu::Menu),
+    // a new board being shown scrambled, it can be played once the animation finishes or is skipped
+    Scrambling(ScrambleAnimation),
+    // moving tiles, by hand, by auto-solving or by watching a replay
+    Playing,
+    Paused(menu::PauseMenu),
+    Solved,
+    // arranging a position by hand before playing it
+    Editor(editor::Editor),
+    Stats {
+        statistics: stats::Statistics,
+        // the board size shown in the graph, an index into the sizes of the statistics
+        size: usize,
+        // the state the statistics were opened from, it goes on once they are closed
+        previous: Box<GameState>,
+    },
+}
+
+impl GameState {
+    // opens the statistics over the state, or closes them again. nothing changes if the statistics
+    // can't be loaded
+    pub fn toggle_stats(self, board: &Board) -> GameState {
+        match self {
+            GameState::Stats { previous, .. } => *previous,
+            previous => match load_statistics(board) {
+                Some((statistics, size)) => GameState::Stats {
+                    statistics,
+                    size,
+                    previous: Box::new(previous),
+                },
+                None => previous,
+            },
+        }
+    }
+
+    // the scramble being shown, also while the statistics are open over it
+    pub fn scramble_animation(&self) -> Option<&ScrambleAnimation> {
+        match self {
+            GameState::Scrambling(animation) => Some(animation),
+            GameState::Stats { previous, .. } => previous.scramble_animation(),
+            _ => None,
+        }
+    }
+
+    // whether no game was started yet, the statistics can be open over the menu. the editor comes
+    // from the menu and its position isn't played yet either
+    pub fn is_menu(&self) -> bool {
+        match self {
+            GameState::Menu(_) | GameState::Editor(_) => true,
+            GameState::Stats { previous, .. } => previous.is_menu(),
+            _ => false,
+        }
+    }
+}
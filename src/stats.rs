@@ -0,0 +1,569 @@
+use crate::events::{Event, EventBus, Subscriber};
+use crate::solver::{self, SolverHeuristic, SolverJob};
+use crate::{daily, storage, Board, Direction, Game, AUTO_SOLVE_MAX_NODES};
+#[cfg(not(target_arch = "wasm32"))]
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const CSV_EXPORT_PATH: &str = "solves.csv";
+// the number of solves shown in the trend graph of the statistics
+pub const GRAPH_SOLVES: usize = 50;
+
+// a finished game, as stored in the solve history
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SolveRecord {
+    // seconds since the unix epoch
+    pub date: u64,
+    pub width: u8,
+    pub height: u8,
+    pub seed: Option<u64>,
+    pub moves: u32,
+    pub undos: u32,
+    pub time_ms: u64,
+    // the length of a shortest solution of the scramble, or a lower bound on it if the solver
+    // gave up. None while it is still being computed
+    pub optimal_moves: Option<u32>,
+    #[serde(default)]
+    pub optimal_is_estimate: bool,
+    // the bests of the board size this solve beat, None where it was no new personal best
+    #[serde(default)]
+    pub previous_best_time_ms: Option<u64>,
+    #[serde(default)]
+    pub previous_best_moves: Option<u32>,
+}
+
+impl SolveRecord {
+    pub fn new(game: &Game) -> SolveRecord {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        SolveRecord {
+            date,
+            width: game.board.width,
+            height: game.board.height,
+            seed: game.seed,
+            moves: game.move_count,
+            undos: game.undo_count,
+            time_ms: game.timer.elapsed().as_millis() as u64,
+            optimal_moves: None,
+            optimal_is_estimate: false,
+            previous_best_time_ms: None,
+            previous_best_moves: None,
+        }
+    }
+
+    // compares the solve to the earlier solves of the same size and keeps the bests it beat.
+    // the first solve of a size has nothing to beat
+    pub fn check_personal_best(&mut self, history: &[SolveRecord]) {
+        let same_size = || {
+            history
+                .iter()
+                .filter(|record| record.width == self.width && record.height == self.height)
+        };
+        let best_time = same_size().map(|record| record.time_ms).min();
+        let best_moves = same_size().map(|record| record.moves).min();
+
+        self.previous_best_time_ms = best_time.filter(|best_time| self.time_ms < *best_time);
+        self.previous_best_moves = best_moves.filter(|best_moves| self.moves < *best_moves);
+    }
+
+    pub fn is_personal_best(&self) -> bool {
+        self.previous_best_time_ms.is_some() || self.previous_best_moves.is_some()
+    }
+
+    // the utc day of the solve, counted like the daily puzzles
+    pub fn day(&self) -> i64 {
+        (self.date / 86400) as i64
+    }
+
+    // the optimal move count as a percentage of the moves used, 100 for a perfect solve
+    pub fn efficiency(&self) -> Option<u32> {
+        self.optimal_moves
+            .filter(|_| self.moves > 0)
+            .map(|optimal_moves| optimal_moves * 100 / self.moves)
+    }
+
+    // e.g. "93% (56/52)", with a ~ when the optimal move count is only estimated
+    pub fn format_efficiency(&self) -> Option<String> {
+        let efficiency = self.efficiency()?;
+        let approximate = if self.optimal_is_estimate { "~" } else { "" };
+
+        Some(format!(
+            "{}{}% ({}/{})",
+            approximate,
+            efficiency,
+            self.moves,
+            self.optimal_moves?
+        ))
+    }
+}
+
+// e.g. ~/.local/share/fifteen-puzzle/stats.sqlite3 on linux
+#[cfg(not(target_arch = "wasm32"))]
+pub fn database_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("stats.sqlite3"))
+}
+
+// solves used to be kept here, one json solve per line, before there was a database. the browser has
+// no sqlite, they are still kept like this there
+fn legacy_history_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("solves.jsonl"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn open() -> Result<Connection, String> {
+    let path = database_path().ok_or("no data directory on this platform")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("could not create {}: {}", parent.display(), error))?;
+    }
+
+    let mut connection =
+        Connection::open(&path).map_err(|error| format!("could not open {}: {}", path.display(), error))?;
+
+    connection
+        .execute(
+            "CREATE TABLE IF NOT EXISTS solves (
+                date INTEGER NOT NULL,
+                width INTEGER NOT NULL,
+                height INTEGER NOT NULL,
+                seed INTEGER,
+                moves INTEGER NOT NULL,
+                undos INTEGER NOT NULL,
+                time_ms INTEGER NOT NULL,
+                optimal_moves INTEGER,
+                optimal_is_estimate INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|error| format!("could not set up {}: {}", path.display(), error))?;
+
+    migrate(&connection).map_err(|error| format!("could not update {}: {}", path.display(), error))?;
+    import_legacy_history(&mut connection)?;
+    Ok(connection)
+}
+
+// changes to the table since it was first created, in order. the database's user_version counts how
+// many of them were applied already
+#[cfg(not(target_arch = "wasm32"))]
+const MIGRATIONS: [&str; 1] = ["ALTER TABLE solves ADD COLUMN previous_best_time_ms INTEGER;
+    ALTER TABLE solves ADD COLUMN previous_best_moves INTEGER;"];
+
+#[cfg(not(target_arch = "wasm32"))]
+fn migrate(connection: &Connection) -> rusqlite::Result<()> {
+    let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (applied, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        connection.execute_batch(migration)?;
+        connection.execute_batch(&format!("PRAGMA user_version = {}", applied + 1))?;
+    }
+
+    Ok(())
+}
+
+// moves the solves from the old history file into the database. the file is renamed afterwards,
+// so they are only imported once
+#[cfg(not(target_arch = "wasm32"))]
+fn import_legacy_history(connection: &mut Connection) -> Result<(), String> {
+    let path = match legacy_history_path().filter(|path| path.exists()) {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    let history = fs::read_to_string(&path).map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    let transaction = connection.transaction().map_err(|error| error.to_string())?;
+
+    for record in history.lines().filter_map(|line| serde_json::from_str::<SolveRecord>(line).ok()) {
+        insert(&transaction, &record)?;
+    }
+
+    transaction.commit().map_err(|error| error.to_string())?;
+    fs::rename(&path, path.with_extension("jsonl.imported"))
+        .map_err(|error| format!("could not rename {}: {}", path.display(), error))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn insert(connection: &Connection, record: &SolveRecord) -> Result<(), String> {
+    connection
+        .execute(
+            "INSERT INTO solves (date, width, height, seed, moves, undos, time_ms, optimal_moves, optimal_is_estimate,
+                previous_best_time_ms, previous_best_moves)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                record.date,
+                record.width,
+                record.height,
+                record.seed,
+                record.moves,
+                record.undos,
+                record.time_ms,
+                record.optimal_moves,
+                record.optimal_is_estimate,
+                record.previous_best_time_ms,
+                record.previous_best_moves,
+            ],
+        )
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn append(record: &SolveRecord) -> Result<(), String> {
+    insert(&open()?, record)
+}
+
+// every solve in the history, oldest first
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load() -> Result<Vec<SolveRecord>, String> {
+    let connection = open()?;
+    let mut statement = connection
+        .prepare(
+            "SELECT date, width, height, seed, moves, undos, time_ms, optimal_moves, optimal_is_estimate,
+                previous_best_time_ms, previous_best_moves
+            FROM solves ORDER BY date",
+        )
+        .map_err(|error| error.to_string())?;
+    let records = statement
+        .query_map([], |row| {
+            Ok(SolveRecord {
+                date: row.get(0)?,
+                width: row.get(1)?,
+                height: row.get(2)?,
+                seed: row.get(3)?,
+                moves: row.get(4)?,
+                undos: row.get(5)?,
+                time_ms: row.get(6)?,
+                optimal_moves: row.get(7)?,
+                optimal_is_estimate: row.get(8)?,
+                previous_best_time_ms: row.get(9)?,
+                previous_best_moves: row.get(10)?,
+            })
+        })
+        .map_err(|error| error.to_string())?;
+
+    records
+        .collect::<Result<Vec<SolveRecord>, _>>()
+        .map_err(|error| format!("could not read the solve history: {}", error))
+}
+
+// localStorage only holds whole values, the history is written again with the solve added
+#[cfg(target_arch = "wasm32")]
+pub fn append(record: &SolveRecord) -> Result<(), String> {
+    let (path, mut history) = read_history()?;
+    history += &serde_json::to_string(record).map_err(|error| error.to_string())?;
+    history.push('\n');
+
+    storage::write(&path, &history).map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load() -> Result<Vec<SolveRecord>, String> {
+    let (_, history) = read_history()?;
+
+    Ok(history.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+// the history's path and its solves, there are none before the first one is appended
+#[cfg(target_arch = "wasm32")]
+fn read_history() -> Result<(PathBuf, String), String> {
+    let path = legacy_history_path().ok_or("no data directory on this platform")?;
+
+    match storage::read_to_string(&path) {
+        Ok(history) => Ok((path, history)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok((path, String::new())),
+        Err(error) => Err(format!("could not read {}: {}", path.display(), error)),
+    }
+}
+
+// one solve per line, dates in utc. none of the values can contain a comma, so nothing is quoted
+pub fn to_csv(records: &[SolveRecord]) -> String {
+    let mut csv = "date,size,seed,time_ms,moves,optimal_moves\n".to_owned();
+
+    for record in records {
+        let seconds = record.date % 86400;
+
+        csv += &format!(
+            "{} {:02}:{:02}:{:02},{}x{},{},{},{},{}\n",
+            daily::format_date(record.day()),
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            record.width,
+            record.height,
+            record.seed.map_or(String::new(), |seed| seed.to_string()),
+            record.time_ms,
+            record.moves,
+            record.optimal_moves.map_or(String::new(), |moves| moves.to_string()),
+        );
+    }
+
+    csv
+}
+
+// writes the whole solve history and returns the number of solves written
+pub fn export_csv(path: &Path) -> Result<usize, String> {
+    let records = load()?;
+
+    fs::write(path, to_csv(&records)).map_err(|error| format!("could not write {}: {}", path.display(), error))?;
+    Ok(records.len())
+}
+
+// the best solves of one board size
+pub struct Leaderboard {
+    pub width: u8,
+    pub height: u8,
+    // the fastest solves, at most LEADERBOARD_SIZE of them
+    pub fastest: Vec<SolveRecord>,
+    pub fewest_moves: Option<u32>,
+}
+
+impl Leaderboard {
+    pub fn new(records: Vec<SolveRecord>, width: u8, height: u8) -> Leaderboard {
+        let mut records: Vec<SolveRecord> = records
+            .into_iter()
+            .filter(|record| record.width == width && record.height == height)
+            .collect();
+
+        let fewest_moves = records.iter().map(|record| record.moves).min();
+        records.sort_by_key(|record| record.time_ms);
+        records.truncate(LEADERBOARD_SIZE);
+
+        Leaderboard {
+            width,
+            height,
+            fastest: records,
+            fewest_moves,
+        }
+    }
+}
+
+// like the averages of speedcubing timers: the mean of the last count times, leaving out the best and
+// the worst one. None with fewer than count times
+pub fn average_of(times: &[u64], count: usize) -> Option<Duration> {
+    if count < 3 || times.len() < count {
+        return None;
+    }
+
+    let mut last: Vec<u64> = times[times.len() - count..].to_vec();
+    last.sort();
+
+    let counted = &last[1..count - 1];
+    Some(Duration::from_millis(counted.iter().sum::<u64>() / counted.len() as u64))
+}
+
+// records the solves that count towards the stats. they go into the history once the solver worked
+// out their optimal move count
+pub struct Recorder {
+    // the current board's solve
+    pub record: Option<SolveRecord>,
+    job: Option<SolverJob>,
+    heuristic: SolverHeuristic,
+}
+
+impl Recorder {
+    pub fn new(heuristic: SolverHeuristic) -> Recorder {
+        Recorder {
+            record: None,
+            job: None,
+            heuristic,
+        }
+    }
+
+    // the optimal move count of the solve once it is known, with whether it is only a lower bound
+    pub fn optimal(&self) -> Option<(u32, bool)> {
+        let record = self.record.as_ref()?;
+        record.optimal_moves.map(|moves| (moves, record.optimal_is_estimate))
+    }
+
+    pub fn update(&mut self, game: &Game) {
+        if let Some(job) = self.job.take() {
+            match (job.poll(), self.record.as_mut()) {
+                (Some(solution), Some(record)) => finish(record, solution, job.board()),
+                (Some(_), None) => {}
+                (None, _) => self.job = Some(job),
+            }
+        }
+
+        // a new board was started before the optimal move count of the last one was known
+        if !game.board.solved {
+            self.finish();
+        }
+    }
+
+    // adds a solve still waiting on the solver to the history, with a lower bound of its optimal
+    // move count
+    pub fn finish(&mut self) {
+        if let (Some(job), Some(mut record)) = (self.job.take(), self.record.take()) {
+            let scramble = job.board().clone();
+            job.cancel();
+            finish(&mut record, None, &scramble);
+        }
+
+        self.record = None;
+    }
+}
+
+impl Subscriber for Recorder {
+    // every solved board is announced, whether it counts towards the stats or not
+    fn on_event(&mut self, event: Event, game: &Game, events: &mut EventBus) {
+        if !matches!(event, Event::MoveApplied { solved: true, .. }) {
+            return;
+        }
+
+        let mut personal_best = false;
+
+        if game.counts_for_stats() {
+            let mut record = SolveRecord::new(game);
+
+            match load() {
+                Ok(history) => record.check_personal_best(&history),
+                Err(error) => eprintln!("{}", error),
+            }
+
+            personal_best = record.is_personal_best();
+            self.record = Some(record);
+            self.job = Some(SolverJob::start(&game.start_board(), self.heuristic, AUTO_SOLVE_MAX_NODES));
+        }
+
+        events.publish(Event::BoardSolved { personal_best });
+    }
+}
+
+// fills in the optimal move count of a solve, falling back to a lower bound if the solver found no
+// solution in time, and adds the solve to the history
+fn finish(record: &mut SolveRecord, solution: Option<Vec<Direction>>, scramble: &Board) {
+    match solution {
+        Some(moves) => record.optimal_moves = Some(moves.len() as u32),
+        None => {
+            record.optimal_moves = Some(solver::manhattan_distance(scramble));
+            record.optimal_is_estimate = true;
+        }
+    }
+
+    if let Err(error) = append(record) {
+        eprintln!("could not save solve: {}", error);
+    }
+}
+
+// the solve times of one board size, oldest first, for the averages shown while playing
+pub struct RecentTimes {
+    pub width: u8,
+    pub height: u8,
+    times: Vec<u64>,
+}
+
+impl RecentTimes {
+    pub fn new(records: &[SolveRecord], width: u8, height: u8) -> RecentTimes {
+        RecentTimes {
+            width,
+            height,
+            times: records
+                .iter()
+                .filter(|record| record.width == width && record.height == height)
+                .map(|record| record.time_ms)
+                .collect(),
+        }
+    }
+
+    pub fn push(&mut self, time: Duration) {
+        self.times.push(time.as_millis() as u64);
+    }
+
+    pub fn average_of(&self, count: usize) -> Option<Duration> {
+        average_of(&self.times, count)
+    }
+}
+
+impl Subscriber for RecentTimes {
+    // only solves that count towards the stats go into the averages
+    fn on_event(&mut self, event: Event, game: &Game, _: &mut EventBus) {
+        if matches!(event, Event::BoardSolved { .. }) && game.counts_for_stats() {
+            self.push(game.timer.elapsed());
+        }
+    }
+}
+
+// solve times of one board size
+pub struct SizeStatistics {
+    pub width: u8,
+    pub height: u8,
+    pub solves: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub best: Duration,
+    pub worst: Duration,
+    pub ao5: Option<Duration>,
+    pub ao12: Option<Duration>,
+    // every time in milliseconds, oldest first
+    pub times: Vec<u64>,
+}
+
+impl SizeStatistics {
+    // None without any solves. the records have to be in the order they were solved in
+    fn new(records: &[&SolveRecord]) -> Option<SizeStatistics> {
+        let times: Vec<u64> = records.iter().map(|record| record.time_ms).collect();
+        let (ao5, ao12) = (average_of(&times, 5), average_of(&times, 12));
+        let mut sorted_times = times.clone();
+        sorted_times.sort();
+
+        let first = records.first()?;
+        let middle = sorted_times.len() / 2;
+        let median = if sorted_times.len().is_multiple_of(2) {
+            (sorted_times[middle - 1] + sorted_times[middle]) / 2
+        } else {
+            sorted_times[middle]
+        };
+
+        Some(SizeStatistics {
+            width: first.width,
+            height: first.height,
+            solves: times.len(),
+            mean: Duration::from_millis(times.iter().sum::<u64>() / times.len() as u64),
+            median: Duration::from_millis(median),
+            best: Duration::from_millis(sorted_times[0]),
+            worst: Duration::from_millis(sorted_times[sorted_times.len() - 1]),
+            ao5,
+            ao12,
+            times,
+        })
+    }
+}
+
+// totals over the whole solve history, and the times of every board size that was solved
+pub struct Statistics {
+    pub solves: usize,
+    pub moves: u64,
+    pub time_played: Duration,
+    // smallest boards first
+    pub sizes: Vec<SizeStatistics>,
+}
+
+impl Statistics {
+    pub fn new(records: &[SolveRecord]) -> Statistics {
+        let mut sizes: Vec<(u8, u8)> = records.iter().map(|record| (record.width, record.height)).collect();
+        sizes.sort_by_key(|(width, height)| (*width as u32 * *height as u32, *width));
+        sizes.dedup();
+
+        Statistics {
+            solves: records.len(),
+            moves: records.iter().map(|record| record.moves as u64).sum(),
+            time_played: Duration::from_millis(records.iter().map(|record| record.time_ms).sum()),
+            sizes: sizes
+                .into_iter()
+                .filter_map(|(width, height)| {
+                    let size_records: Vec<&SolveRecord> = records
+                        .iter()
+                        .filter(|record| record.width == width && record.height == height)
+                        .collect();
+                    SizeStatistics::new(&size_records)
+                })
+                .collect(),
+        }
+    }
+}
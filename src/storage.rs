@@ -0,0 +1,107 @@
+// where the config, the saves and the records are kept. on the desktop they are files in the
+// platform's folders, in the browser they are kept in the page's localStorage under their path
+#[cfg(not(target_arch = "wasm32"))]
+pub use files::*;
+#[cfg(target_arch = "wasm32")]
+pub use local_storage::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod files {
+    use std::fs;
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    pub fn data_dir() -> Option<PathBuf> {
+        dirs::data_dir()
+    }
+
+    pub fn config_dir() -> Option<PathBuf> {
+        dirs::config_dir()
+    }
+
+    pub fn exists(path: &Path) -> bool {
+        path.exists()
+    }
+
+    pub fn read_to_string(path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    pub fn write(path: &Path, contents: &str) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    pub fn remove_file(path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod local_storage {
+    use std::ffi::{CStr, CString};
+    use std::io;
+    use std::os::raw::c_char;
+    use std::path::{Path, PathBuf};
+
+    extern "C" {
+        // evaluates javascript in the page, null and undefined come back as a null pointer. the
+        // string is emscripten's and only valid until the next call
+        fn emscripten_run_script_string(script: *const c_char) -> *const c_char;
+    }
+
+    // e.g. data/fifteen-puzzle/autosave.json, the folders only show up in the keys
+    pub fn data_dir() -> Option<PathBuf> {
+        Some(PathBuf::from("data"))
+    }
+
+    pub fn config_dir() -> Option<PathBuf> {
+        Some(PathBuf::from("config"))
+    }
+
+    pub fn exists(path: &Path) -> bool {
+        run(&format!("localStorage.getItem({})", quote(&path.to_string_lossy()))).is_some()
+    }
+
+    pub fn read_to_string(path: &Path) -> io::Result<String> {
+        run(&format!("localStorage.getItem({})", quote(&path.to_string_lossy())))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "not in the local storage"))
+    }
+
+    // the storage can be full or turned off, its error is passed on
+    pub fn write(path: &Path, contents: &str) -> io::Result<()> {
+        let script = format!(
+            "(function () {{ try {{ localStorage.setItem({}, {}); return null; }} \
+            catch (error) {{ return String(error); }} }})()",
+            quote(&path.to_string_lossy()),
+            quote(contents)
+        );
+
+        match run(&script) {
+            Some(error) => Err(io::Error::other(error)),
+            None => Ok(()),
+        }
+    }
+
+    // removing a key that isn't there does nothing, unlike removing a missing file
+    pub fn remove_file(path: &Path) -> io::Result<()> {
+        run(&format!("localStorage.removeItem({})", quote(&path.to_string_lossy())));
+        Ok(())
+    }
+
+    // a javascript string of the text, json's strings are valid javascript
+    fn quote(text: &str) -> String {
+        serde_json::Value::from(text).to_string()
+    }
+
+    fn run(script: &str) -> Option<String> {
+        // the quoted strings escape nul characters, so there are none in the script
+        let script = CString::new(script).ok()?;
+        let result = unsafe { emscripten_run_script_string(script.as_ptr()) };
+
+        if result.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(result) }.to_string_lossy().into_owned())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+// the best streak of the streak mode. the current streak isn't kept, quitting breaks it
+#[derive(Default, Serialize, Deserialize)]
+pub struct StreakRecord {
+    pub best: u32,
+}
+
+// e.g. ~/.local/share/fifteen-puzzle/streak.json on linux
+pub fn record_path() -> Option<PathBuf> {
+    storage::data_dir().map(|dir| dir.join("fifteen-puzzle").join("streak.json"))
+}
+
+// a missing record means there was no streak yet
+pub fn load() -> StreakRecord {
+    let path = match record_path().filter(|path| storage::exists(path)) {
+        Some(path) => path,
+        None => return StreakRecord::default(),
+    };
+
+    let result = storage::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|error| error.to_string()));
+
+    match result {
+        Ok(record) => record,
+        Err(error) => {
+            eprintln!("invalid streak record {}: {}", path.display(), error);
+            StreakRecord::default()
+        }
+    }
+}
+
+pub fn save(record: &StreakRecord) -> Result<(), String> {
+    let path = record_path().ok_or("no data directory on this platform")?;
+    let json = serde_json::to_string_pretty(record).map_err(|error| error.to_string())?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("could not create {}: {}", parent.display(), error))?;
+    }
+
+    storage::write(&path, &json).map_err(|error| format!("could not write {}: {}", path.display(), error))
+}
@@ -0,0 +1,123 @@
+use crate::config::{BuiltinTheme, Colors};
+use crate::storage;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+// how often the themes folder is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+// custom themes are toml files in ~/.config/fifteen-puzzle/themes (or the platform equivalent)
+// with the same fields as the colors of the config file. a theme is picked by its file name
+// without the extension, e.g. "ocean" for ocean.toml
+pub fn themes_dir() -> Option<PathBuf> {
+    storage::config_dir().map(|dir| dir.join("fifteen-puzzle").join("themes"))
+}
+
+pub struct Theme {
+    // what the theme is called in the config file and on the command line
+    pub id: String,
+    pub name: String,
+    pub colors: Colors,
+}
+
+// the built-in themes followed by the ones from the themes folder
+pub struct Themes {
+    themes: Vec<Theme>,
+    // the theme files with when they were last changed
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    last_check: Instant,
+}
+
+impl Themes {
+    pub fn load() -> Themes {
+        let mut themes = Themes {
+            themes: Vec::new(),
+            files: Vec::new(),
+            last_check: Instant::now(),
+        };
+        themes.reload();
+        themes
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.id == id)
+    }
+
+    // the theme after the one with the id, wrapping around to the first
+    pub fn next(&self, id: Option<&str>) -> &Theme {
+        self.step(id, 1)
+    }
+
+    // the theme steps after (or before, for a negative step) the one with the id, wrapping around.
+    // an unknown id (or custom colors from the config file) counts as the dark theme, which comes first
+    pub fn step(&self, id: Option<&str>, step: i32) -> &Theme {
+        let index = self.themes.iter().position(|theme| Some(theme.id.as_str()) == id).unwrap_or(0);
+        &self.themes[(index as i32 + step).rem_euclid(self.themes.len() as i32) as usize]
+    }
+
+    // loads the theme files again if any of them were added, changed or removed since the last
+    // check. returns whether they were
+    pub fn reload_if_changed(&mut self) -> bool {
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return false;
+        }
+
+        self.last_check = Instant::now();
+
+        if find_files() == self.files {
+            return false;
+        }
+
+        self.reload();
+        true
+    }
+
+    fn reload(&mut self) {
+        self.files = find_files();
+        self.themes = BuiltinTheme::ALL
+            .iter()
+            .map(|theme| Theme {
+                id: theme.id().to_owned(),
+                name: theme.name().to_owned(),
+                colors: theme.colors(),
+            })
+            .collect();
+
+        // a broken theme file is reported and left out, the others still load
+        for (path, _) in &self.files {
+            let id = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            let colors = fs::read_to_string(path)
+                .map_err(|error| error.to_string())
+                .and_then(|text| toml::from_str(&text).map_err(|error| error.to_string()));
+
+            match colors {
+                Ok(colors) => self.themes.push(Theme {
+                    name: id.clone(),
+                    id,
+                    colors,
+                }),
+                Err(error) => eprintln!("invalid theme file {}: {}", path.display(), error),
+            }
+        }
+    }
+}
+
+fn find_files() -> Vec<(PathBuf, Option<SystemTime>)> {
+    let entries = match themes_dir().map(fs::read_dir) {
+        Some(Ok(entries)) => entries,
+        _ => return Vec::new(),
+    };
+
+    let mut files: Vec<(PathBuf, Option<SystemTime>)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|extension| extension == "toml"))
+        .map(|entry| {
+            let modified = entry.metadata().and_then(|metadata| metadata.modified()).ok();
+            (entry.path(), modified)
+        })
+        .collect();
+
+    files.sort();
+    files
+}
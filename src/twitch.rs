@@ -0,0 +1,149 @@
+use crate::Direction;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// twitch chat plays the board: viewers vote with !up, !down, !left and !right in the channel's chat,
+// and the move with the most votes is made at the end of every round of voting. the chat is read
+// from twitch's irc server without logging in, so no account or token is needed
+
+const SERVER: &str = "irc.chat.twitch.tv:6667";
+// twitch lets anyone read chat under a justinfan name
+const NICK: &str = "justinfan15015";
+
+pub const VOTES: [(&str, Direction); 4] = [
+    ("!up", Direction::Up),
+    ("!down", Direction::Down),
+    ("!left", Direction::Left),
+    ("!right", Direction::Right),
+];
+
+enum ChatMessage {
+    Joined,
+    // a viewer's vote, by their name
+    Vote(String, Direction),
+}
+
+pub struct ChatPlays {
+    pub channel: String,
+    receiver: Receiver<ChatMessage>,
+    joined: bool,
+    closed: bool,
+    // each viewer's vote in this round, a later vote replaces their earlier one
+    votes: HashMap<String, Direction>,
+    interval: Duration,
+    round_ends: Instant,
+}
+
+impl ChatPlays {
+    pub fn connect(channel: &str, interval: Duration) -> ChatPlays {
+        let channel = channel.trim_start_matches('#').to_lowercase();
+        let (sender, receiver) = mpsc::channel();
+        let joined_channel = channel.clone();
+
+        // the thread ends once the connection does, and with it the receiver
+        thread::spawn(move || {
+            let _ = read_chat(&joined_channel, &sender);
+        });
+
+        ChatPlays {
+            channel,
+            receiver,
+            joined: false,
+            closed: false,
+            votes: HashMap::new(),
+            // a round of no time at all would make a move every frame
+            interval: interval.max(Duration::from_secs(1)),
+            round_ends: Instant::now() + interval,
+        }
+    }
+
+    // takes in the votes since the last frame, and returns chat's move once the round is over. a
+    // round without votes makes no move
+    pub fn update(&mut self) -> Option<Direction> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(ChatMessage::Joined) => self.joined = true,
+                Ok(ChatMessage::Vote(viewer, direction)) => {
+                    self.votes.insert(viewer, direction);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+
+        if Instant::now() < self.round_ends {
+            return None;
+        }
+
+        self.round_ends = Instant::now() + self.interval;
+        let tallies = self.tallies();
+        self.votes.clear();
+
+        // ties go to the first of them in the order of the votes
+        let (direction, votes) = tallies.iter().rev().max_by_key(|(_, votes)| *votes)?;
+        (*votes > 0).then_some(*direction)
+    }
+
+    // drops the votes of the round so far and starts it over, e.g. while the board can't be played
+    pub fn restart_round(&mut self) {
+        self.votes.clear();
+        self.round_ends = Instant::now() + self.interval;
+    }
+
+    pub fn tallies(&self) -> [(Direction, usize); 4] {
+        VOTES.map(|(_, direction)| (direction, self.votes.values().filter(|vote| **vote == direction).count()))
+    }
+
+    pub fn time_left(&self) -> Duration {
+        self.round_ends.saturating_duration_since(Instant::now())
+    }
+
+    // whether chat was joined, even if the connection was lost since
+    pub fn has_joined(&self) -> bool {
+        self.joined
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+fn read_chat(channel: &str, sender: &Sender<ChatMessage>) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect(SERVER)?;
+    stream.write_all(("NICK ".to_owned() + NICK + "\r\nJOIN #" + channel + "\r\n").as_bytes())?;
+    let _ = sender.send(ChatMessage::Joined);
+
+    for line in BufReader::new(stream.try_clone()?).lines() {
+        let line = line?;
+
+        // the server drops connections that don't answer its pings
+        if let Some(server) = line.strip_prefix("PING") {
+            stream.write_all(("PONG".to_owned() + server + "\r\n").as_bytes())?;
+        } else if let Some((viewer, vote)) = parse_vote(&line) {
+            if sender.send(ChatMessage::Vote(viewer, vote)).is_err() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// a chat message is sent as ":<name>!<name>@<name>.tmi.twitch.tv PRIVMSG #<channel> :<text>", it
+// votes if its first word is one of the votes
+fn parse_vote(line: &str) -> Option<(String, Direction)> {
+    let (prefix, rest) = line.strip_prefix(':')?.split_once(' ')?;
+    let text = rest.strip_prefix("PRIVMSG ")?.split_once(" :")?.1;
+    let viewer = prefix.split('!').next()?;
+
+    let word = text.split_whitespace().next()?.to_lowercase();
+    let (_, direction) = VOTES.iter().find(|(vote, _)| *vote == word)?;
+    Some((viewer.to_owned(), *direction))
+}
@@ -0,0 +1,67 @@
+use crate::{Board, Direction, Timer};
+use std::time::Duration;
+
+// the second player of a split-screen race at the same keyboard. they get a board of their own
+// with the same scramble, and their timer starts with their own first move
+pub struct Versus {
+    pub board: Board,
+    start: Board,
+    pub move_count: u32,
+    timer: Timer,
+    // whether the first player solved their board before the second one, once either did
+    first_won: Option<bool>,
+}
+
+impl Versus {
+    pub fn new(start: &Board) -> Versus {
+        Versus {
+            board: start.clone(),
+            start: start.clone(),
+            move_count: 0,
+            timer: Timer::new(),
+            first_won: None,
+        }
+    }
+
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    pub fn time(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    // the second player's clock stops with the first one's, e.g. while the game is paused
+    pub fn update(&mut self, playing: bool) {
+        if !playing {
+            self.timer.stop();
+        } else if self.move_count > 0 && self.first_won.is_none() {
+            self.timer.start();
+        }
+    }
+
+    pub fn move_empty(&mut self, direction: Direction) {
+        // the race is over for both once one of them has won
+        if self.first_won.is_some() || !self.board.move_empty(direction) {
+            return;
+        }
+
+        self.move_count += 1;
+        self.timer.start();
+
+        if self.board.solved {
+            self.timer.stop();
+            self.first_won = Some(false);
+        }
+    }
+
+    // the first player solved their board, they won unless the second one was done already
+    pub fn first_solved(&mut self) {
+        self.timer.stop();
+        self.first_won.get_or_insert(true);
+    }
+
+    pub fn first_won(&self) -> Option<bool> {
+        self.first_won
+    }
+}